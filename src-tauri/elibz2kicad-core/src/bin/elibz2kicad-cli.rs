@@ -0,0 +1,210 @@
+//! elibz2kicad-cli - 命令行批量转换，不启动GUI
+//!
+//! 用法: elibz2kicad-cli convert <文件.elibz>... [--footprints <目录>] [--symbols <文件.kicad_sym>] [--replace] [--polygon-arcs] [--chord-tolerance <mm>]
+//!
+//! 退出码: 0 全部转换完成；1 有文件、符号或封装转换失败，或有未能转换的图元；2 参数错误
+
+use std::process::ExitCode;
+
+use elibz2kicad_core::{convert_elibz_file, ConversionReport, ConvertOptions, ItemKind, ItemStatus};
+
+const USAGE: &str = "用法: elibz2kicad-cli convert <文件.elibz>... [选项]
+
+选项:
+  -f, --footprints <目录>        封装输出目录，不存在时自动创建
+  -s, --symbols <文件.kicad_sym> 符号库文件，不存在时自动创建
+  -r, --replace                  替换库中的同名符号
+      --polygon-arcs             填充图形中的圆弧直接输出，需要KiCad 7及以上
+      --chord-tolerance <mm>     圆弧拟合为线段时的最大弦高误差，默认0.005
+  -h, --help                     显示帮助";
+
+/// 命令行参数
+struct Args {
+    elibz_files: Vec<String>,
+    footprints: String,
+    symbols: String,
+    replace: bool,
+    options: ConvertOptions,
+}
+
+/// 解析命令行参数
+///
+/// # 返回值
+///
+/// * `Ok(Some(Args))` - 参数
+/// * `Ok(None)` - 只显示帮助
+/// * `Err(String)` - 参数错误
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Args>, String> {
+    match args.next().as_deref() {
+        Some("convert") => {}
+        Some("-h") | Some("--help") => return Ok(None),
+        Some(command) => return Err(format!("未知的命令: {}", command)),
+        None => return Err("缺少命令".to_string()),
+    }
+
+    let mut parsed = Args {
+        elibz_files: Vec::new(),
+        footprints: String::new(),
+        symbols: String::new(),
+        replace: false,
+        options: ConvertOptions::default(),
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-f" | "--footprints" => {
+                parsed.footprints = args.next().ok_or("--footprints 缺少目录")?;
+            }
+            "-s" | "--symbols" => {
+                parsed.symbols = args.next().ok_or("--symbols 缺少文件")?;
+            }
+            "-r" | "--replace" => parsed.replace = true,
+            "--polygon-arcs" => parsed.options.polygon_arcs = true,
+            "--chord-tolerance" => {
+                let value = args.next().ok_or("--chord-tolerance 缺少数值")?;
+                parsed.options.chord_tolerance = match value.parse::<f64>() {
+                    Ok(tolerance) if tolerance > 0.0 => tolerance,
+                    _ => return Err(format!("--chord-tolerance 不是正数: {}", value)),
+                };
+            }
+            "-h" | "--help" => return Ok(None),
+            _ if arg.starts_with('-') => return Err(format!("未知的选项: {}", arg)),
+            _ => parsed.elibz_files.push(arg),
+        }
+    }
+
+    if parsed.elibz_files.is_empty() {
+        return Err("未指定.elibz文件".to_string());
+    }
+    if parsed.footprints.is_empty() && parsed.symbols.is_empty() {
+        return Err("至少需要指定 --footprints 或 --symbols".to_string());
+    }
+    Ok(Some(parsed))
+}
+
+// 打印一个.elibz文件的转换结果
+fn print_report(report: &ConversionReport) {
+    println!("{}:", report.source);
+    for item in &report.items {
+        let kind = match item.kind {
+            ItemKind::Symbol => "符号",
+            ItemKind::Footprint => "封装",
+        };
+        let status = match item.status {
+            ItemStatus::Converted => "已转换",
+            ItemStatus::Skipped => "已跳过",
+            ItemStatus::Failed => "失败",
+        };
+        match &item.message {
+            Some(message) => println!("  {} {}: {}，{}", kind, item.title, status, message),
+            None => println!("  {} {}: {}", kind, item.title, status),
+        }
+        if let Some(output_path) = &item.output_path {
+            println!("    输出: {}", output_path);
+        }
+        for warning in &item.warnings {
+            println!("    警告: {}", warning);
+        }
+        for unconverted in &item.unconverted {
+            println!("    未完全解析: 第{}行 {} {}", unconverted.line, unconverted.kind, unconverted.reason);
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    if !args.footprints.is_empty() {
+        if let Err(e) = std::fs::create_dir_all(&args.footprints) {
+            eprintln!("无法创建封装输出目录{}: {}", args.footprints, e);
+            return ExitCode::from(1);
+        }
+    }
+
+    // 失败的文件数、未能转换的图元数
+    let mut failed_count = 0;
+    let mut unconverted_count = 0;
+    for elibz_file in &args.elibz_files {
+        match convert_elibz_file(elibz_file, &args.footprints, &args.symbols, args.replace, &args.options) {
+            Ok(report) => {
+                print_report(&report);
+                if report.has_failures() {
+                    failed_count += 1;
+                }
+                unconverted_count += report.unconverted_count();
+            }
+            Err(e) => {
+                eprintln!("{}: 转换失败: {}", elibz_file, e);
+                failed_count += 1;
+            }
+        }
+    }
+
+    println!(
+        "共{}个文件，{}个有失败，未完全解析{}处",
+        args.elibz_files.len(),
+        failed_count,
+        unconverted_count
+    );
+    if failed_count > 0 || unconverted_count > 0 {
+        ExitCode::from(1)
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Option<Args>, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn parses_options() {
+        let args = parse(&["convert", "a.elibz", "-f", "out", "b.elibz", "--symbols", "lib.kicad_sym", "-r", "--polygon-arcs", "--chord-tolerance", "0.01"])
+            .unwrap()
+            .unwrap();
+        assert_eq!(args.elibz_files, vec!["a.elibz", "b.elibz"]);
+        assert_eq!(args.footprints, "out");
+        assert_eq!(args.symbols, "lib.kicad_sym");
+        assert!(args.replace);
+        assert!(args.options.polygon_arcs);
+        assert_eq!(args.options.chord_tolerance, 0.01);
+
+        let args = parse(&["convert", "a.elibz", "--footprints", "out"]).unwrap().unwrap();
+        assert!(!args.replace);
+        assert_eq!(args.options, ConvertOptions::default());
+    }
+
+    #[test]
+    fn help_is_not_an_error() {
+        assert!(parse(&["--help"]).unwrap().is_none());
+        assert!(parse(&["convert", "a.elibz", "-h"]).unwrap().is_none());
+    }
+
+    #[test]
+    fn rejects_bad_arguments() {
+        let error = |args: &[&str]| parse(args).err().unwrap();
+        assert_eq!(error(&[]), "缺少命令");
+        assert_eq!(error(&["export"]), "未知的命令: export");
+        assert_eq!(error(&["convert", "a.elibz", "-f", "out", "--bogus"]), "未知的选项: --bogus");
+        assert_eq!(error(&["convert", "-f", "out"]), "未指定.elibz文件");
+        assert_eq!(error(&["convert", "a.elibz"]), "至少需要指定 --footprints 或 --symbols");
+        assert_eq!(error(&["convert", "a.elibz", "--footprints"]), "--footprints 缺少目录");
+        assert_eq!(error(&["convert", "a.elibz", "-f", "out", "--chord-tolerance"]), "--chord-tolerance 缺少数值");
+        assert_eq!(error(&["convert", "a.elibz", "-f", "out", "--chord-tolerance", "abc"]), "--chord-tolerance 不是正数: abc");
+        assert_eq!(error(&["convert", "a.elibz", "-f", "out", "--chord-tolerance", "0"]), "--chord-tolerance 不是正数: 0");
+        assert_eq!(error(&["convert", "a.elibz", "-f", "out", "--chord-tolerance", "-1"]), "--chord-tolerance 不是正数: -1");
+    }
+}
@@ -0,0 +1,675 @@
+//! jlc_model - EasyEDA Pro封装(.efoo)数据的中间模型
+//!
+//! efoo文件每行是一个JSON数组，这里把每行解析成带类型的图元，
+//! 字段缺失或类型不对时返回带行号的错误，而不是在转换时panic
+
+use std::fmt;
+use serde_json::Value;
+
+/// efoo行解析错误
+#[derive(Debug, Clone, PartialEq)]
+pub struct EfooError {
+    /// 行号，从1开始
+    pub line: usize,
+    /// 图元类型，无法读取类型时为空
+    pub kind: String,
+    /// 错误说明
+    pub message: String,
+}
+
+impl fmt::Display for EfooError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "第{}行{}: {}", self.line, self.kind, self.message)
+    }
+}
+
+/// 路径中的一段，起点为上一段的终点
+#[derive(Debug, Clone, PartialEq)]
+pub enum EfooSegment {
+    /// 直线，"L"之后的每对坐标是一段
+    Line { x: f64, y: f64 },
+    /// 圆弧，"ARC"/"CARC"之后依次为圆心角(角度，逆时针为正)和终点坐标
+    Arc { angle: f64, x: f64, y: f64 },
+}
+
+/// 由直线和圆弧组成的路径
+#[derive(Debug, Clone, PartialEq)]
+pub struct EfooPath {
+    /// 起点X坐标
+    pub start_x: f64,
+    /// 起点Y坐标
+    pub start_y: f64,
+    /// 各段
+    pub segments: Vec<EfooSegment>,
+}
+
+/// POLY/FILL中的一个图形
+#[derive(Debug, Clone, PartialEq)]
+pub enum EfooShape {
+    /// ["CIRCLE",cx,cy,r]
+    Circle { center_x: f64, center_y: f64, radius: f64 },
+    /// [x,y,"L",x,y,...,"ARC",angle,x,y,...]
+    Path(EfooPath),
+    /// ["R",x,y,宽,高,旋转角度,圆角半径]，(x,y)为左上角，绕左上角逆时针旋转
+    Rect { x: f64, y: f64, width: f64, height: f64, rotation: f64, radius: f64 },
+    /// 暂不支持的图形，保存说明
+    Unsupported(String),
+}
+
+/// 焊盘钻孔
+#[derive(Debug, Clone, PartialEq)]
+pub enum EfooHole {
+    /// ["ROUND",宽,高]，圆孔
+    Round { width: f64, height: f64 },
+    /// ["SLOT",宽,高]，槽孔
+    Slot { width: f64, height: f64 },
+    /// 其它钻孔形状
+    Unknown(String),
+}
+
+/// 焊盘形状
+#[derive(Debug, Clone, PartialEq)]
+pub enum EfooPadShape {
+    /// ["ELLIPSE",宽,高]
+    Ellipse { width: f64, height: f64 },
+    /// ["RECT",宽,高,圆角半径]，圆角半径为短边的百分比，50时短边两端为半圆
+    Rect { width: f64, height: f64, radius: f64 },
+    /// ["OVAL",宽,高]
+    Oval { width: f64, height: f64 },
+    /// ["POLY",[x,y,"L",x,y,...]]，坐标为绝对坐标
+    Poly(EfooPath),
+    /// 其它焊盘形状
+    Unknown(String),
+}
+
+/// 焊盘
+#[derive(Debug, Clone, PartialEq)]
+pub struct EfooPad {
+    /// 所在层
+    pub layer: u64,
+    /// 焊盘编号
+    pub number: String,
+    /// 中心坐标
+    pub x: f64,
+    pub y: f64,
+    /// 旋转角度
+    pub rotation: f64,
+    /// 钻孔，贴片焊盘为None
+    pub hole: Option<EfooHole>,
+    /// 焊盘形状
+    pub shape: EfooPadShape,
+    /// 钻孔中心相对焊盘中心的偏移，焊盘自身的坐标系
+    pub hole_offset_x: f64,
+    pub hole_offset_y: f64,
+    /// 钻孔相对焊盘的旋转角度
+    pub hole_rotation: f64,
+    /// 通孔是否金属化
+    pub plated: bool,
+    /// 阻焊扩展
+    pub solder_mask_margin: f64,
+    /// 锡膏扩展
+    pub solder_paste_margin: f64,
+}
+
+/// 过孔
+#[derive(Debug, Clone, PartialEq)]
+pub struct EfooVia {
+    /// 网络名称
+    pub net: String,
+    /// 中心坐标
+    pub x: f64,
+    pub y: f64,
+    /// 钻孔直径
+    pub drill: f64,
+    /// 外径
+    pub diameter: f64,
+}
+
+/// 文本
+#[derive(Debug, Clone, PartialEq)]
+pub struct EfooString {
+    /// 所在层
+    pub layer: u64,
+    /// 位置
+    pub x: f64,
+    pub y: f64,
+    /// 文本内容
+    pub text: String,
+    /// 字体名称
+    pub font: String,
+    /// 字高
+    pub height: f64,
+    /// 笔画宽度
+    pub stroke_width: f64,
+    /// 对齐方式，1~9依次为左上、中上、右上、左中、中心、右中、左下、中下、右下
+    pub align: u64,
+    /// 旋转角度
+    pub rotation: f64,
+    /// 是否镜像
+    pub mirror: bool,
+}
+
+/// 属性，如位号、BOM标志
+#[derive(Debug, Clone, PartialEq)]
+pub struct EfooAttr {
+    /// 所属图元ID，为空时是封装自身的属性
+    pub parent: String,
+    /// 所在层
+    pub layer: u64,
+    /// 显示位置，未放置时为None
+    pub x: Option<f64>,
+    pub y: Option<f64>,
+    /// 属性名
+    pub key: String,
+    /// 属性值
+    pub value: String,
+    /// 属性值是否显示
+    pub visible: bool,
+    /// 字高
+    pub height: f64,
+    /// 笔画宽度
+    pub stroke_width: f64,
+    /// 旋转角度
+    pub rotation: f64,
+}
+
+/// 区域(禁止区域等)
+#[derive(Debug, Clone, PartialEq)]
+pub struct EfooRegion {
+    /// 所在层
+    pub layer: u64,
+    /// 线宽
+    pub width: f64,
+    /// 区域轮廓
+    pub shapes: Vec<EfooShape>,
+    /// 禁止类型编号
+    pub rules: Vec<u64>,
+}
+
+/// efoo文件中的一行
+#[derive(Debug, Clone, PartialEq)]
+pub enum EfooPrimitive {
+    /// POLY为不填充的线条，FILL为填充的图形
+    Graphic { fill: bool, layer: u64, width: f64, shapes: Vec<EfooShape> },
+    /// 焊盘
+    Pad(EfooPad),
+    /// 过孔
+    Via(EfooVia),
+    /// 文本
+    String(EfooString),
+    /// 区域
+    Region(EfooRegion),
+    /// 属性
+    Attr(EfooAttr),
+    /// 暂不处理的其它类型，保存类型名称
+    Other(String),
+}
+
+/// 解析efoo文件中的一行
+///
+/// # 参数
+///
+/// * `line_number` - 行号，从1开始，用于错误信息
+/// * `line` - 行内容
+///
+/// # 返回值
+///
+/// * `Result<Option<EfooPrimitive>, EfooError>` - 空行返回None，格式不对时返回带行号的错误
+pub fn parse_efoo_line(line_number: usize, line: &str) -> Result<Option<EfooPrimitive>, EfooError> {
+    if line.trim().is_empty() {
+        return Ok(None);
+    }
+    let mut fields = Fields { line_number, kind: "", array: &Vec::new() };
+    let json_value: Value = serde_json::from_str(line)
+        .map_err(|e| fields.error(&format!("无法解析JSON: {}", e)))?;
+    let array = json_value.as_array()
+        .ok_or_else(|| fields.error("不是json数组"))?;
+    fields.array = array;
+    let type_name = fields.str(0, "类型")?;
+    fields.kind = type_name;
+
+    let primitive = match type_name {
+        "POLY" | "FILL" => {
+            let fill = type_name == "FILL";
+            EfooPrimitive::Graphic {
+                fill,
+                layer: fields.u64(4, "层")?,
+                width: fields.f64(5, "线宽")?,
+                shapes: parse_shapes(fields.array(if fill { 7 } else { 6 }, "图形")?)
+                    .map_err(|e| fields.error(&e))?,
+            }
+        }
+        "PAD" => EfooPrimitive::Pad(parse_pad(&fields)?),
+        "VIA" => EfooPrimitive::Via(EfooVia {
+            net: fields.str_or(3, ""),
+            x: fields.f64(5, "X坐标")?,
+            y: fields.f64(6, "Y坐标")?,
+            drill: fields.f64(7, "钻孔直径")?,
+            diameter: fields.f64(8, "外径")?,
+        }),
+        "STRING" => EfooPrimitive::String(EfooString {
+            layer: fields.u64(3, "层")?,
+            x: fields.f64(4, "X坐标")?,
+            y: fields.f64(5, "Y坐标")?,
+            text: fields.str(6, "文本")?.to_string(),
+            font: fields.str_or(7, "default"),
+            height: fields.f64(8, "字高")?,
+            stroke_width: fields.f64_or(9, 0.0),
+            align: fields.u64_or(12, 1),
+            rotation: fields.f64_or(13, 0.0),
+            mirror: fields.bool_or(16, false),
+        }),
+        "REGION" => EfooPrimitive::Region(EfooRegion {
+            layer: fields.u64(3, "层")?,
+            width: fields.f64_or(4, 0.0),
+            shapes: parse_shapes(fields.array(5, "区域轮廓")?).map_err(|e| fields.error(&e))?,
+            rules: array.get(6)
+                .and_then(|v| v.as_array())
+                .map(|rules| rules.iter().filter_map(|r| r.as_u64()).collect())
+                .unwrap_or_default(),
+        }),
+        // ["ATTR",ID,锁定,所属ID,层,x,y,属性名,属性值,属性名是否显示,属性值是否显示,字体,字高,笔画宽度,_,_,对齐方式,旋转角度,...]
+        "ATTR" => EfooPrimitive::Attr(EfooAttr {
+            parent: fields.str_or(3, ""),
+            layer: fields.u64_or(4, 3),
+            x: array.get(5).and_then(|v| v.as_f64()),
+            y: array.get(6).and_then(|v| v.as_f64()),
+            key: fields.str(7, "属性名")?.to_string(),
+            value: match array.get(8) {
+                Some(Value::String(s)) => s.clone(),
+                Some(Value::Number(n)) => n.to_string(),
+                _ => String::new(),
+            },
+            visible: fields.bool_or(10, false),
+            height: fields.f64_or(12, 0.0),
+            stroke_width: fields.f64_or(13, 0.0),
+            rotation: fields.f64_or(17, 0.0),
+        }),
+        _ => EfooPrimitive::Other(type_name.to_string()),
+    };
+    Ok(Some(primitive))
+}
+
+// 解析焊盘
+fn parse_pad(fields: &Fields) -> Result<EfooPad, EfooError> {
+    if fields.array.len() < 11 {
+        return Err(fields.error("焊盘数据长度不足"));
+    }
+    // 钻孔可能是数组也可能是null
+    let hole = match fields.array.get(9).and_then(|v| v.as_array()) {
+        Some(hole) if !hole.is_empty() => Some(parse_hole(hole).map_err(|e| fields.error(&e))?),
+        _ => None,
+    };
+    let shape = parse_pad_shape(fields.array(10, "焊盘形状")?).map_err(|e| fields.error(&e))?;
+    Ok(EfooPad {
+        layer: fields.u64_or(4, 1),
+        number: fields.str_or(5, ""),
+        x: fields.f64_or(6, 0.0),
+        y: fields.f64_or(7, 0.0),
+        rotation: fields.f64_or(8, 0.0),
+        hole,
+        shape,
+        hole_offset_x: fields.f64_or(12, 0.0),
+        hole_offset_y: fields.f64_or(13, 0.0),
+        hole_rotation: fields.f64_or(14, 0.0),
+        plated: fields.bool_or(15, true),
+        solder_mask_margin: fields.f64_or(18, 2.0),
+        solder_paste_margin: fields.f64_or(20, 0.0),
+    })
+}
+
+// 解析钻孔
+fn parse_hole(hole: &[Value]) -> Result<EfooHole, String> {
+    let kind = hole[0].as_str().unwrap_or("");
+    let width = hole.get(1).and_then(|v| v.as_f64()).ok_or("钻孔缺少宽度")?;
+    let height = hole.get(2).and_then(|v| v.as_f64()).unwrap_or(width);
+    Ok(match kind {
+        "ROUND" => EfooHole::Round { width, height },
+        "SLOT" => EfooHole::Slot { width, height },
+        _ => EfooHole::Unknown(kind.to_string()),
+    })
+}
+
+// 解析焊盘形状
+fn parse_pad_shape(shape: &[Value]) -> Result<EfooPadShape, String> {
+    let kind = shape.first().and_then(|v| v.as_str()).unwrap_or("");
+    let num = |i: usize| shape.get(i).and_then(|v| v.as_f64());
+    Ok(match kind {
+        "ELLIPSE" => {
+            let width = num(1).ok_or("圆形焊盘缺少尺寸")?;
+            EfooPadShape::Ellipse { width, height: num(2).unwrap_or(width) }
+        }
+        "RECT" => EfooPadShape::Rect {
+            width: num(1).ok_or("矩形焊盘缺少宽度")?,
+            height: num(2).ok_or("矩形焊盘缺少高度")?,
+            radius: num(3).unwrap_or(0.0),
+        },
+        "OVAL" => EfooPadShape::Oval {
+            width: num(1).ok_or("椭圆焊盘缺少宽度")?,
+            height: num(2).ok_or("椭圆焊盘缺少高度")?,
+        },
+        "POLY" => {
+            let points = shape.get(1).and_then(|v| v.as_array()).ok_or("多边形焊盘缺少点")?;
+            match parse_shape(points)? {
+                EfooShape::Path(path) => EfooPadShape::Poly(path),
+                _ => EfooPadShape::Unknown("POLY".to_string()),
+            }
+        }
+        _ => EfooPadShape::Unknown(kind.to_string()),
+    })
+}
+
+/// 解析POLY/FILL/REGION中的图形数组，可能是单个图形，也可能是多个轮廓组成的数组
+///
+/// # 参数
+///
+/// * `values` - 图形数组
+///
+/// # 返回值
+///
+/// * `Result<Vec<EfooShape>, String>` - 各个轮廓
+pub fn parse_shapes(values: &[Value]) -> Result<Vec<EfooShape>, String> {
+    if values.first().map(|v| v.is_array()).unwrap_or(false) {
+        values.iter()
+            .map(|v| parse_shape(v.as_array().map(|a| a.as_slice()).unwrap_or(&[])))
+            .collect()
+    } else {
+        Ok(vec![parse_shape(values)?])
+    }
+}
+
+/// 解析单个图形
+///
+/// # 参数
+///
+/// * `values` - ["CIRCLE",cx,cy,r]、["R",x,y,宽,高,旋转角度,圆角半径]或[x,y,"L",x,y,...,"ARC",angle,x,y,...]
+///
+/// # 返回值
+///
+/// * `Result<EfooShape, String>` - 图形，无法识别的图形返回EfooShape::Unsupported
+pub fn parse_shape(values: &[Value]) -> Result<EfooShape, String> {
+    let num = |i: usize| values.get(i).and_then(|v| v.as_f64());
+    if let Some(kind) = values.first().and_then(|v| v.as_str()) {
+        if kind == "CIRCLE" {
+            return match (num(1), num(2), num(3)) {
+                (Some(center_x), Some(center_y), Some(radius)) => Ok(EfooShape::Circle { center_x, center_y, radius }),
+                _ => Err("圆形数据不完整".to_string()),
+            };
+        }
+        if kind == "R" {
+            return match (num(1), num(2), num(3), num(4)) {
+                (Some(x), Some(y), Some(width), Some(height)) => Ok(EfooShape::Rect {
+                    x,
+                    y,
+                    width,
+                    height,
+                    rotation: num(5).unwrap_or(0.0),
+                    radius: num(6).unwrap_or(0.0),
+                }),
+                _ => Err("矩形数据不完整".to_string()),
+            };
+        }
+        return Ok(EfooShape::Unsupported(format!("图形类型{}", kind)));
+    }
+
+    let (start_x, start_y) = match (num(0), num(1)) {
+        (Some(x), Some(y)) => (x, y),
+        _ => return Err("路径缺少起点".to_string()),
+    };
+    let mut segments = Vec::new();
+    let mut i = 2;
+    while i < values.len() {
+        match values[i].as_str() {
+            Some("L") => {
+                // 连续读取坐标，直到遇到非数字
+                i += 1;
+                while let (Some(x), Some(y)) = (num(i), num(i + 1)) {
+                    segments.push(EfooSegment::Line { x, y });
+                    i += 2;
+                }
+            }
+            Some("ARC") | Some("CARC") => {
+                match (num(i + 1), num(i + 2), num(i + 3)) {
+                    (Some(angle), Some(x), Some(y)) => segments.push(EfooSegment::Arc { angle, x, y }),
+                    _ => return Err("圆弧数据不完整".to_string()),
+                }
+                i += 4;
+            }
+            Some(other) => return Ok(EfooShape::Unsupported(format!("路径中的{}", other))),
+            None => return Err(format!("路径第{}项不是关键字", i)),
+        }
+    }
+    if segments.is_empty() {
+        return Ok(EfooShape::Unsupported("没有直线或圆弧的路径".to_string()));
+    }
+    Ok(EfooShape::Path(EfooPath { start_x, start_y, segments }))
+}
+
+// 带行号的数组字段读取
+struct Fields<'a> {
+    line_number: usize,
+    kind: &'a str,
+    array: &'a Vec<Value>,
+}
+
+impl<'a> Fields<'a> {
+    fn error(&self, message: &str) -> EfooError {
+        EfooError {
+            line: self.line_number,
+            kind: self.kind.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    fn f64(&self, index: usize, name: &str) -> Result<f64, EfooError> {
+        self.array.get(index).and_then(|v| v.as_f64())
+            .ok_or_else(|| self.error(&format!("缺少{}(第{}项)", name, index)))
+    }
+
+    fn u64(&self, index: usize, name: &str) -> Result<u64, EfooError> {
+        self.array.get(index).and_then(|v| v.as_u64())
+            .ok_or_else(|| self.error(&format!("缺少{}(第{}项)", name, index)))
+    }
+
+    fn str(&self, index: usize, name: &str) -> Result<&'a str, EfooError> {
+        self.array.get(index).and_then(|v| v.as_str())
+            .ok_or_else(|| self.error(&format!("缺少{}(第{}项)", name, index)))
+    }
+
+    fn array(&self, index: usize, name: &str) -> Result<&'a [Value], EfooError> {
+        self.array.get(index).and_then(|v| v.as_array()).map(|a| a.as_slice())
+            .ok_or_else(|| self.error(&format!("缺少{}(第{}项)", name, index)))
+    }
+
+    fn f64_or(&self, index: usize, default: f64) -> f64 {
+        self.array.get(index).and_then(|v| v.as_f64()).unwrap_or(default)
+    }
+
+    fn u64_or(&self, index: usize, default: u64) -> u64 {
+        self.array.get(index).and_then(|v| v.as_u64()).unwrap_or(default)
+    }
+
+    fn str_or(&self, index: usize, default: &str) -> String {
+        self.array.get(index).and_then(|v| v.as_str()).unwrap_or(default).to_string()
+    }
+
+    fn bool_or(&self, index: usize, default: bool) -> bool {
+        match self.array.get(index) {
+            Some(Value::Bool(b)) => *b,
+            Some(Value::Number(n)) => n.as_f64().map(|n| n != 0.0).unwrap_or(default),
+            _ => default,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn error(line: usize, kind: &str, message: &str) -> EfooError {
+        EfooError { line, kind: kind.to_string(), message: message.to_string() }
+    }
+
+    fn pad_line(hole: Value, shape: Value) -> String {
+        json!(["PAD", "e1", 0, "", 1, "1", 10, 20, 90, hole, shape, [], 0, 0, 45, 1, 0, 2, 3, 0, 1]).to_string()
+    }
+
+    #[test]
+    fn empty_line_is_skipped() {
+        assert_eq!(parse_efoo_line(1, "  "), Ok(None));
+    }
+
+    #[test]
+    fn parses_pad() {
+        let line = pad_line(json!(["SLOT", 20, 40]), json!(["RECT", 60, 80, 25]));
+        let pad = match parse_efoo_line(1, &line) {
+            Ok(Some(EfooPrimitive::Pad(pad))) => pad,
+            other => panic!("不是焊盘: {:?}", other),
+        };
+        assert_eq!(pad.number, "1");
+        assert_eq!((pad.x, pad.y, pad.rotation, pad.hole_rotation), (10.0, 20.0, 90.0, 45.0));
+        assert!(pad.plated);
+        assert_eq!(pad.hole, Some(EfooHole::Slot { width: 20.0, height: 40.0 }));
+        assert_eq!(pad.shape, EfooPadShape::Rect { width: 60.0, height: 80.0, radius: 25.0 });
+        assert_eq!((pad.solder_mask_margin, pad.solder_paste_margin), (3.0, 1.0));
+    }
+
+    #[test]
+    fn parses_unplated_pad() {
+        let line = json!(["PAD", "e1", 0, "", 12, "1", 0, 0, 0, ["ROUND", 20], ["ELLIPSE", 60], [], 0, 0, 0, 0]).to_string();
+        match parse_efoo_line(1, &line) {
+            Ok(Some(EfooPrimitive::Pad(pad))) => assert!(!pad.plated),
+            other => panic!("不是焊盘: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn smd_pad_has_no_hole() {
+        let line = pad_line(Value::Null, json!(["ELLIPSE", 30]));
+        match parse_efoo_line(1, &line) {
+            Ok(Some(EfooPrimitive::Pad(pad))) => {
+                assert_eq!(pad.hole, None);
+                assert_eq!(pad.shape, EfooPadShape::Ellipse { width: 30.0, height: 30.0 });
+            }
+            other => panic!("不是焊盘: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn short_pad_is_error() {
+        assert_eq!(parse_efoo_line(3, r#"["PAD","bad"]"#), Err(error(3, "PAD", "焊盘数据长度不足")));
+    }
+
+    #[test]
+    fn unknown_hole_and_pad_kinds() {
+        let line = pad_line(json!(["TRIANGLE", 20]), json!(["STAR", 60]));
+        match parse_efoo_line(1, &line) {
+            Ok(Some(EfooPrimitive::Pad(pad))) => {
+                assert_eq!(pad.hole, Some(EfooHole::Unknown("TRIANGLE".to_string())));
+                assert_eq!(pad.shape, EfooPadShape::Unknown("STAR".to_string()));
+            }
+            other => panic!("不是焊盘: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn incomplete_hole_and_pad_shape_are_errors() {
+        let line = pad_line(json!(["ROUND"]), json!(["ELLIPSE", 60]));
+        assert_eq!(parse_efoo_line(2, &line), Err(error(2, "PAD", "钻孔缺少宽度")));
+        let line = pad_line(Value::Null, json!(["RECT", 60]));
+        assert_eq!(parse_efoo_line(2, &line), Err(error(2, "PAD", "矩形焊盘缺少高度")));
+        let line = pad_line(Value::Null, json!("RECT"));
+        assert_eq!(parse_efoo_line(2, &line), Err(error(2, "PAD", "缺少焊盘形状(第10项)")));
+    }
+
+    #[test]
+    fn wrong_field_types_are_errors() {
+        assert_eq!(
+            parse_efoo_line(4, r#"["POLY","e1",0,"",3,"wide",[0,0,"L",10,0]]"#),
+            Err(error(4, "POLY", "缺少线宽(第5项)"))
+        );
+        assert_eq!(
+            parse_efoo_line(5, r#"["VIA","e1",0,"",0,"x",0,12,24]"#),
+            Err(error(5, "VIA", "缺少X坐标(第5项)"))
+        );
+        assert_eq!(
+            parse_efoo_line(6, r#"["STRING","e1",0,"3",0,0,"A"]"#),
+            Err(error(6, "STRING", "缺少层(第3项)"))
+        );
+        assert_eq!(parse_efoo_line(7, r#"[1,2]"#), Err(error(7, "", "缺少类型(第0项)")));
+        assert_eq!(parse_efoo_line(8, r#"{"a":1}"#), Err(error(8, "", "不是json数组")));
+        match parse_efoo_line(9, "[\"PAD\"") {
+            Err(e) => {
+                assert_eq!((e.line, e.kind.as_str()), (9, ""));
+                assert!(e.message.starts_with("无法解析JSON"));
+            }
+            other => panic!("应该解析失败: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_graphic_with_contours() {
+        let line = r#"["FILL","e1",0,"",3,0,0,[["CIRCLE",0,0,10],[0,0,"L",10,0,"ARC",90,0,10]]]"#;
+        assert_eq!(
+            parse_efoo_line(1, line),
+            Ok(Some(EfooPrimitive::Graphic {
+                fill: true,
+                layer: 3,
+                width: 0.0,
+                shapes: vec![
+                    EfooShape::Circle { center_x: 0.0, center_y: 0.0, radius: 10.0 },
+                    EfooShape::Path(EfooPath {
+                        start_x: 0.0,
+                        start_y: 0.0,
+                        segments: vec![
+                            EfooSegment::Line { x: 10.0, y: 0.0 },
+                            EfooSegment::Arc { angle: 90.0, x: 0.0, y: 10.0 },
+                        ],
+                    }),
+                ],
+            }))
+        );
+    }
+
+    #[test]
+    fn unknown_type_is_other() {
+        assert_eq!(parse_efoo_line(1, r#"["BOGUS","x"]"#), Ok(Some(EfooPrimitive::Other("BOGUS".to_string()))));
+    }
+
+    #[test]
+    fn parse_shape_variants() {
+        let shape = |v: Value| parse_shape(v.as_array().unwrap());
+        assert_eq!(shape(json!(["CIRCLE", 1, 2, 3])), Ok(EfooShape::Circle { center_x: 1.0, center_y: 2.0, radius: 3.0 }));
+        assert_eq!(
+            shape(json!(["R", 0, 10, 20, 10])),
+            Ok(EfooShape::Rect { x: 0.0, y: 10.0, width: 20.0, height: 10.0, rotation: 0.0, radius: 0.0 })
+        );
+        assert_eq!(
+            shape(json!([0, 0, "L", 1, 0, 1, 1, "CARC", -90, 0, 0])),
+            Ok(EfooShape::Path(EfooPath {
+                start_x: 0.0,
+                start_y: 0.0,
+                segments: vec![
+                    EfooSegment::Line { x: 1.0, y: 0.0 },
+                    EfooSegment::Line { x: 1.0, y: 1.0 },
+                    EfooSegment::Arc { angle: -90.0, x: 0.0, y: 0.0 },
+                ],
+            }))
+        );
+        assert_eq!(shape(json!(["TRIANGLE", 1])), Ok(EfooShape::Unsupported("图形类型TRIANGLE".to_string())));
+        assert_eq!(shape(json!([0, 0, "Q", 1, 1])), Ok(EfooShape::Unsupported("路径中的Q".to_string())));
+        assert_eq!(shape(json!([0, 0])), Ok(EfooShape::Unsupported("没有直线或圆弧的路径".to_string())));
+    }
+
+    #[test]
+    fn parse_shape_errors() {
+        let shape = |v: Value| parse_shape(v.as_array().unwrap());
+        assert_eq!(shape(json!(["CIRCLE", 1, 2])), Err("圆形数据不完整".to_string()));
+        assert_eq!(shape(json!(["R", 0, 10, 20])), Err("矩形数据不完整".to_string()));
+        assert_eq!(shape(json!(["x", 0])), Ok(EfooShape::Unsupported("图形类型x".to_string())));
+        assert_eq!(shape(json!([0])), Err("路径缺少起点".to_string()));
+        assert_eq!(shape(json!([0, 0, 5])), Err("路径第2项不是关键字".to_string()));
+        assert_eq!(shape(json!([0, 0, "ARC", 90, 1])), Err("圆弧数据不完整".to_string()));
+    }
+}
@@ -0,0 +1,2359 @@
+
+use std::io::{Cursor, Read};
+use zip::ZipArchive;
+use log::{error, info, warn}; // 添加日志库引用
+use serde_json::{Value}; // 添加JSON处理库引用
+use crate::kicad_tool::KicadModData;
+use crate::kicad_tool::KicadSymData;
+use crate::kicad_tool::{arc_segment_count, fit_arc_with_lines, mil_to_mm, OutlineSegment, PadDrill, PadGeometry, PadMargins, Point};
+use crate::report::{ConvertError, ItemKind, ItemReport};
+use crate::jlc_model::{parse_efoo_line, EfooAttr, EfooHole, EfooPad, EfooPadShape, EfooPath, EfooPrimitive, EfooSegment, EfooShape, EfooVia};
+use std::collections::HashMap;
+
+
+// 层映射   
+// 1 顶层 -> F.Cu
+// 3 顶层丝印层 -> F.Silkscreen
+// 5 顶层阻焊层 -> F.Mask
+// 7 顶层锡膏层 -> F.Paste
+// 9 顶层装配层 -> F.Fab
+
+// 2 底层 -> B.Cu
+// 4 底层丝印层 -> B.Silkscreen
+// 6 底层阻焊层 -> B.Mask
+// 8 底层锡膏层 -> B.Paste
+// 10 底层装配层 -> B.Fab
+
+// 11 板框层 -> Edge.Cuts
+// 12 多层 -> F&B.Cu *.Mask
+// 13 文档层 -> User.Drawings
+// 14 机械层 -> null
+// 56 钻孔图层 -> null
+// 57 飞线层 -> null
+
+// 48 元件外形层 -> F.Fab
+// 49 元件标识层 -> User.7
+// 50 引脚焊接层 -> User.8
+// 51 引脚悬空层 -> User.9
+const LAYER_MAP: &[(u64, &str)] = &[
+    (1, "F.Cu"),
+    (2, "B.Cu"),
+    (3, "F.SilkS"),
+    (4, "B.SilkS"),
+    (5, "F.Mask"),
+    (6, "B.Mask"),
+    (7, "F.Paste"),
+    (8, "B.Paste"),
+    (9, "F.Fab"),
+    (10, "B.Fab"),
+    (11, "Edge.Cuts"),
+    (12, "F&B.Cu *.Mask"),
+    (13, "Dwgs.User"),
+    (48, "F.Fab"),
+    (49, "User.7"),
+    (50, "User.8"),
+    (51, "User.9"),
+];
+
+// efoo中不含图形的记录，忽略时不计入未转换
+const NON_GEOMETRY_TYPES: &[&str] = &[
+    "DOCTYPE",
+    "HEAD",
+    "CANVAS",
+    "LAYER",
+    "LAYER_PHYS",
+    "ACTIVE_LAYER",
+    "NET",
+    "RULE_TEMPLATE",
+    "RULE",
+    "RULE_SELECTOR",
+    "PRIMITIVE",
+    "PREFERENCE",
+    "SILK_OPTS",
+    "CONNECT",
+];
+
+// 内层编号：15 内层1 ... 46 内层32，KiCad最多30个内层
+const INNER_LAYER_FIRST: u64 = 15;
+const INNER_LAYER_COUNT: u64 = 30;
+
+// 贴片焊盘所在的层，EasyEDA层 -> KiCad的铜、锡膏、阻焊层
+// 顶层、底层带锡膏和阻焊，内层只有铜
+fn smd_pad_layers(layer: u64) -> Option<String> {
+    match layer {
+        1 => Some("\"F.Cu\" \"F.Paste\" \"F.Mask\"".to_string()),
+        2 => Some("\"B.Cu\" \"B.Paste\" \"B.Mask\"".to_string()),
+        _ if (INNER_LAYER_FIRST..INNER_LAYER_FIRST + INNER_LAYER_COUNT).contains(&layer) => {
+            Some(format!("\"In{}.Cu\"", layer - INNER_LAYER_FIRST + 1))
+        }
+        _ => None,
+    }
+}
+
+// 禁止区域所在的铜层，EasyEDA层 -> KiCad铜层，多层为所有铜层
+fn region_layers(layer: u64) -> Option<String> {
+    match layer {
+        1 => Some("\"F.Cu\"".to_string()),
+        2 => Some("\"B.Cu\"".to_string()),
+        12 => Some("\"*.Cu\"".to_string()),
+        _ if (INNER_LAYER_FIRST..INNER_LAYER_FIRST + INNER_LAYER_COUNT).contains(&layer) => {
+            Some(format!("\"In{}.Cu\"", layer - INNER_LAYER_FIRST + 1))
+        }
+        _ => None,
+    }
+}
+
+// 区域禁止类型映射，EasyEDA编号 -> KiCad禁止区域的类型
+// 2 禁止元件、3 禁止过孔、5 禁止导线、6 禁止填充、7 禁止铺铜
+const REGION_RULE_MAP: &[(u64, &str)] = &[
+    (2, "footprints"),
+    (3, "vias"),
+    (5, "tracks"),
+    (6, "copperpour"),
+    (7, "copperpour"),
+];
+
+// 符号属性映射，EasyEDA属性名 -> KiCad属性名
+const SYMBOL_ATTR_MAP: &[(&str, &str)] = &[
+    ("Description", "ki_description"),
+    ("Datasheet", "Datasheet"),
+    ("Manufacturer", "Manufacturer"),
+    ("Manufacturer Part", "MPN"),
+    ("Supplier", "Supplier"),
+    ("Supplier Part", "Supplier Part"),
+];
+
+// 引脚电气类型映射，EasyEDA电气类型 -> KiCad引脚类型
+// 数字为旧版的编号：0 未定义、1 输入、2 输出、3 双向、4 电源
+const PIN_TYPE_MAP: &[(&str, &str)] = &[
+    ("0", "unspecified"),
+    ("1", "input"),
+    ("2", "output"),
+    ("3", "bidirectional"),
+    ("4", "power_in"),
+    ("UNDEFINED", "unspecified"),
+    ("UNSPECIFIED", "unspecified"),
+    ("IN", "input"),
+    ("INPUT", "input"),
+    ("OUT", "output"),
+    ("OUTPUT", "output"),
+    ("BI", "bidirectional"),
+    ("IO", "bidirectional"),
+    ("I/O", "bidirectional"),
+    ("BIDIRECTIONAL", "bidirectional"),
+    ("TRI", "tri_state"),
+    ("TRI_STATE", "tri_state"),
+    ("TRISTATE", "tri_state"),
+    ("PASSIVE", "passive"),
+    ("PWR", "power_in"),
+    ("POWER", "power_in"),
+    ("POWER_IN", "power_in"),
+    ("GND", "power_in"),
+    ("POWER_OUT", "power_out"),
+    ("OC", "open_collector"),
+    ("OPEN_COLLECTOR", "open_collector"),
+    ("OE", "open_emitter"),
+    ("OPEN_EMITTER", "open_emitter"),
+    ("NC", "no_connect"),
+    ("NO_CONNECT", "no_connect"),
+];
+
+// 引脚形状映射，EasyEDA引脚形状 -> KiCad引脚图形样式
+// 数字编号：0 无、1 反相圆点、2 时钟、3 反相圆点加时钟
+const PIN_SHAPE_MAP: &[(&str, &str)] = &[
+    ("0", "line"),
+    ("1", "inverted"),
+    ("2", "clock"),
+    ("3", "inverted_clock"),
+    ("NONE", "line"),
+    ("LINE", "line"),
+    ("DOT", "inverted"),
+    ("INVERTED", "inverted"),
+    ("NOT", "inverted"),
+    ("CLK", "clock"),
+    ("CLOCK", "clock"),
+    ("DOT_CLK", "inverted_clock"),
+    ("CLK_DOT", "inverted_clock"),
+    ("INVERTED_CLOCK", "inverted_clock"),
+    ("INPUT_LOW", "input_low"),
+    ("ACTIVE_LOW_INPUT", "input_low"),
+    ("OUTPUT_LOW", "output_low"),
+    ("ACTIVE_LOW_OUTPUT", "output_low"),
+    ("CLOCK_LOW", "clock_low"),
+    ("FALLING_EDGE", "edge_clock_high"),
+    ("NON_LOGIC", "non_logic"),
+];
+
+
+
+
+/// 转换选项
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConvertOptions {
+    /// 填充图形中的圆弧直接输出到fp_poly中，文件版本随之改为KiCad 7的20221018；否则拟合为线段
+    pub polygon_arcs: bool,
+    /// 圆弧拟合为线段时，线段与圆弧之间允许的最大距离，单位mm
+    pub chord_tolerance: f64,
+}
+
+impl Default for ConvertOptions {
+    fn default() -> Self {
+        ConvertOptions {
+            polygon_arcs: false,
+            chord_tolerance: 0.005,
+        }
+    }
+}
+
+/// 转换后的符号
+pub struct ConvertedSymbol {
+    /// KiCad符号
+    pub symbol: KicadSymData,
+    /// 转换结果，标题为符号名称
+    pub report: ItemReport,
+}
+
+/// 转换后的封装
+pub struct ConvertedFootprint {
+    /// .kicad_mod文件内容
+    pub content: String,
+    /// 转换结果，标题为封装名称
+    pub report: ItemReport,
+}
+
+/// 一个.elibz文件转换后的全部符号和封装
+pub struct ConvertedLibrary {
+    pub symbols: Vec<ConvertedSymbol>,
+    pub footprints: Vec<ConvertedFootprint>,
+    /// 压缩包里无法读取的符号和封装
+    pub failed: Vec<ItemReport>,
+}
+
+/// 转换.elibz文件内容，不读写任何文件
+///
+/// # 参数
+///
+/// * `name` - 库名称，JSON清单里没有标题时用作符号、封装的名称
+/// * `bytes` - .elibz文件内容
+/// * `options` - 转换选项
+///
+/// # 返回值
+///
+/// * `Result<ConvertedLibrary, ConvertError>` - 转换后的符号和封装，压缩包或JSON清单无法解析时返回错误
+pub fn convert_elibz(name: &str, bytes: &[u8], options: &ConvertOptions) -> Result<ConvertedLibrary, ConvertError> {
+    let mut archive = ZipArchive::new(Cursor::new(bytes)).map_err(|e| ConvertError::OpenArchive(e.to_string()))?;
+
+    // 确定压缩包里每个文件的扩展名，记录.json文件的次序，以及所有.efoo、.esym文件的次序和UUID(文件名)
+    let mut json_index = None;
+    let mut efoo_files: Vec<(usize, String)> = Vec::new();
+    let mut esym_files: Vec<(usize, String)> = Vec::new();
+    for i in 0..archive.len() {
+        let file = archive.by_index(i).map_err(|e| ConvertError::ReadArchive(e.to_string()))?;
+        let uuid = std::path::Path::new(file.name())
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("")
+            .to_string();
+        if file.name().ends_with(".json") {
+            json_index = Some(i);
+        } else if file.name().ends_with(".efoo") {
+            efoo_files.push((i, uuid));
+        } else if file.name().ends_with(".esym") {
+            esym_files.push((i, uuid));
+        }
+    }
+
+    // 检查是否找到所需文件
+    let json_index = match json_index {
+        Some(index) if !efoo_files.is_empty() || !esym_files.is_empty() => index,
+        _ => return Err(ConvertError::MissingFiles),
+    };
+
+    // 读取json文件内容
+    let json_content = read_archive_file(&mut archive, json_index)?;
+
+    // 解析JSON值
+    let json_value: Value = serde_json::from_str(&json_content)
+        .map_err(|e| ConvertError::InvalidManifest(e.to_string()))?;
+
+    let mut library = ConvertedLibrary {
+        symbols: Vec::new(),
+        footprints: Vec::new(),
+        failed: Vec::new(),
+    };
+
+    // 逐个解析符号，标题按UUID从JSON里"symbols"中获取
+    let symbol_titles = unique_titles(&json_value, "symbols", &esym_files, name);
+    for ((index, _), symbol_title_str) in esym_files.iter().zip(symbol_titles) {
+        match read_archive_file(&mut archive, *index) {
+            Ok(contents) => library.symbols.push(parse_esym_file(&contents, &symbol_title_str)),
+            Err(e) => {
+                error!("读取符号{}失败: {}", symbol_title_str, e);
+                library.failed.push(ItemReport::new(ItemKind::Symbol, &symbol_title_str).failed(e.to_string()));
+            }
+        }
+    }
+
+    // 逐个解析封装，标题按UUID从JSON里"footprints"中获取
+    let footprint_titles = unique_titles(&json_value, "footprints", &efoo_files, name);
+    for ((index, uuid), footprint_title_str) in efoo_files.iter().zip(footprint_titles) {
+        match read_archive_file(&mut archive, *index) {
+            Ok(contents) => {
+                let device_attrs = manifest_device_attributes(&json_value, uuid);
+                library.footprints.push(parse_efoo_file(&contents, &footprint_title_str, &device_attrs, options));
+            }
+            Err(e) => {
+                error!("读取封装{}失败: {}", footprint_title_str, e);
+                library.failed.push(ItemReport::new(ItemKind::Footprint, &footprint_title_str).failed(e.to_string()));
+            }
+        }
+    }
+
+    Ok(library)
+}
+
+// 读取压缩包中第index个文件的内容
+fn read_archive_file(archive: &mut ZipArchive<Cursor<&[u8]>>, index: usize) -> Result<String, ConvertError> {
+    let mut file = archive.by_index(index).map_err(|e| ConvertError::ReadArchive(e.to_string()))?;
+    let mut content = String::new();
+    file.read_to_string(&mut content).map_err(|e| ConvertError::ReadArchive(e.to_string()))?;
+    Ok(content)
+}
+
+/// 按UUID从JSON清单中获取符号或封装的标题
+///
+/// # 参数
+///
+/// * `json_value` - JSON清单
+/// * `category` - "symbols"或"footprints"
+/// * `uuid` - 符号或封装的UUID，即压缩包里的文件名
+/// * `fallback` - 找不到标题时使用的名称
+///
+/// # 返回值
+///
+/// * `String` - 标题；清单里没有该UUID时，若清单中只有一项则使用该项的标题，否则使用fallback加UUID
+fn manifest_title(json_value: &Value, category: &str, uuid: &str, fallback: &str) -> String {
+    let entries = match json_value[category].as_object() {
+        Some(entries) => entries,
+        None => return fallback.to_string(),
+    };
+    let entry = entries.get(uuid).or_else(|| {
+        if entries.len() == 1 {
+            entries.values().next()
+        } else {
+            None
+        }
+    });
+    match entry.and_then(|e| e["display_title"].as_str().or(e["title"].as_str())) {
+        Some(title) => title.to_string(),
+        None if entries.len() > 1 => format!("{}_{}", fallback, uuid),
+        None => fallback.to_string(),
+    }
+}
+
+// 获取压缩包里每个文件的标题，同一类中标题重复时都加上UUID，避免输出时互相覆盖
+fn unique_titles(json_value: &Value, category: &str, files: &[(usize, String)], fallback: &str) -> Vec<String> {
+    let titles: Vec<String> = files.iter().map(|(_, uuid)| manifest_title(json_value, category, uuid, fallback)).collect();
+    titles.iter().zip(files).map(|(title, (_, uuid))| {
+        if titles.iter().filter(|t| *t == title).count() > 1 {
+            warn!("{}的标题{}重复，加上UUID", category, title);
+            format!("{}_{}", title, uuid)
+        } else {
+            title.clone()
+        }
+    }).collect()
+}
+
+/// 从JSON清单的"devices"中获取使用该封装的器件的属性
+///
+/// # 参数
+///
+/// * `json_value` - JSON清单
+/// * `footprint_uuid` - 封装的UUID
+///
+/// # 返回值
+///
+/// * `Vec<(String, String)>` - 属性名和属性值；器件的"Footprint"属性与UUID相同时使用该器件，清单中只有一个器件和一个封装时直接使用该器件，找不到时为空
+fn manifest_device_attributes(json_value: &Value, footprint_uuid: &str) -> Vec<(String, String)> {
+    let devices = match json_value["devices"].as_object() {
+        Some(devices) => devices,
+        None => return Vec::new(),
+    };
+    let single_footprint = json_value["footprints"].as_object().is_some_and(|f| f.len() == 1);
+    let device = devices.values()
+        .find(|d| d["attributes"]["Footprint"].as_str() == Some(footprint_uuid))
+        .or_else(|| if devices.len() == 1 && single_footprint { devices.values().next() } else { None });
+    match device.and_then(|d| d["attributes"].as_object()) {
+        Some(attributes) => attributes.iter()
+            .filter_map(|(key, value)| match value {
+                Value::String(s) => Some((key.clone(), s.clone())),
+                Value::Number(n) => Some((key.clone(), n.to_string())),
+                Value::Bool(b) => Some((key.clone(), b.to_string())),
+                _ => None,
+            })
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+// 封装属性标志
+enum FootprintFlag {
+    ExcludeFromPosFiles(bool),
+    ExcludeFromBom(bool),
+}
+
+// 由EasyEDA属性得到封装属性标志，属性值不是是/否时忽略
+// "Add into BOM"为否时不出现在BOM中，也可以直接使用"Exclude From BOM"、"Exclude From Pos Files"
+fn footprint_flag(key: &str, value: &str) -> Option<FootprintFlag> {
+    let value = match value.trim().to_lowercase().as_str() {
+        "yes" | "true" | "1" | "是" => true,
+        "no" | "false" | "0" | "否" => false,
+        _ => return None,
+    };
+    match normalize_type_key(key).as_str() {
+        "ADD_INTO_BOM" => Some(FootprintFlag::ExcludeFromBom(!value)),
+        "EXCLUDE_FROM_BOM" => Some(FootprintFlag::ExcludeFromBom(value)),
+        "EXCLUDE_FROM_POS_FILES" | "EXCLUDE_FROM_POSITION_FILES" => Some(FootprintFlag::ExcludeFromPosFiles(value)),
+        _ => None,
+    }
+}
+
+// esym文件中的引脚，引脚的名称、编号等属性在ATTR行里，需要先收集再统一生成
+struct EsymPin {
+    /// 所在行号
+    line: usize,
+    /// 所属单元
+    unit: u32,
+    /// 引脚ID，ATTR行通过该ID关联到引脚
+    id: String,
+    /// 是否显示
+    display: bool,
+    /// 连接点坐标
+    x: f64,
+    y: f64,
+    /// 长度
+    length: f64,
+    /// 旋转角度
+    rotation: f64,
+    /// 引脚名称
+    name: String,
+    /// 引脚编号
+    number: String,
+    /// 是否显示引脚名称
+    name_visible: bool,
+    /// 是否显示引脚编号
+    number_visible: bool,
+    /// 电气类型，已规范化为大写
+    electric: Option<String>,
+    /// 引脚形状，已规范化为大写
+    shape: Option<String>,
+}
+
+// 规范化类型名称：转大写并把空格、连字符换成下划线
+fn normalize_type_key(s: &str) -> String {
+    s.trim().to_uppercase().replace([' ', '-'], "_")
+}
+
+// 规范化esym中的类型字段，数字转为字符串
+fn esym_type_key(value: Option<&Value>) -> Option<String> {
+    match value {
+        Some(Value::String(s)) if !s.trim().is_empty() => Some(normalize_type_key(s)),
+        Some(Value::Number(n)) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+// 去掉部件名称末尾的部件序号，如"LM358.1" -> "LM358"
+fn part_base_name(part_name: &str) -> &str {
+    match part_name.rsplit_once('.') {
+        Some((base, index)) if !base.is_empty() && !index.is_empty() && index.chars().all(|c| c.is_ascii_digit()) => base,
+        _ => part_name,
+    }
+}
+
+// 多部件符号的标题可能是某个部件的名称，如"LM358.1"，此时使用去掉部件序号的名称
+fn symbol_title<'a>(title: &'a str, part_names: &[String]) -> &'a str {
+    if part_names.len() > 1 && part_names.iter().any(|name| name == title) {
+        part_base_name(title)
+    } else {
+        title
+    }
+}
+
+// 在映射表中查找
+fn lookup_map(map: &[(&str, &'static str)], key: &str) -> Option<&'static str> {
+    map.iter().find(|(k, _)| *k == key).map(|(_, v)| *v)
+}
+
+// 定义一个esym文件内容解析函数
+fn parse_esym_file(esym_content: &str, symbol_title_str: &str) -> ConvertedSymbol {
+    // 线型: ID -> (线宽, 是否填充)
+    let mut line_styles: HashMap<String, (f64, bool)> = HashMap::new();
+    // 字体: ID -> 字号
+    let mut font_styles: HashMap<String, f64> = HashMap::new();
+    let mut pins: Vec<EsymPin> = Vec::new();
+    // 引脚属性: (引脚ID, 属性名, 属性值, 是否显示)
+    let mut pin_attrs: Vec<(String, String, String, bool)> = Vec::new();
+    // 符号属性: (属性名, 属性值)
+    let mut symbol_attrs: Vec<(String, String)> = Vec::new();
+    // 位号的显示位置: (x, y, 是否显示)
+    let mut designator_position: Option<(f64, f64, bool)> = None;
+    // 图形行，线型和字体可能定义在图形之后，所以先暂存
+    let mut shape_lines: Vec<(usize, u32, Vec<Value>)> = Vec::new();
+    // 部件名称，多部件符号的每个部件对应KiCad的一个单元
+    let mut part_names: Vec<String> = Vec::new();
+    // 当前所在单元，没有PART行时全部放在单元1
+    let mut current_unit: u32 = 1;
+    // 转换结果
+    let mut report = ItemReport::new(ItemKind::Symbol, symbol_title_str);
+
+    for (line_index, line) in esym_content.lines().enumerate() {
+        info!("当前行: {}", line);
+        let line_number = line_index + 1;
+
+        // 跳过空行
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        // 解析JSON
+        let json_value: Value = match serde_json::from_str(line) {
+            Ok(value) => value,
+            Err(e) => {
+                report.unconverted(line_number, "", format!("无法解析JSON: {}", e));
+                continue;
+            }
+        };
+
+        // 检查是否为数组
+        let array = match json_value.as_array() {
+            Some(arr) => arr,
+            None => {
+                report.unconverted(line_number, "", "不是json数组");
+                continue;
+            }
+        };
+
+        let type_name = array.first().and_then(|v| v.as_str()).unwrap_or("");
+        match type_name {
+            "DOCTYPE" | "HEAD" => {
+                info!("文件头信息: {}", type_name);
+            }
+            "PART" => {
+                // ["PART","LM358.1",{"BBOX":[...]}]，之后的图形和引脚都属于该部件，直到下一个PART
+                let part_name = array.get(1).and_then(|v| v.as_str()).unwrap_or("").to_string();
+                info!("部件: {}", part_name);
+                part_names.push(part_name);
+                current_unit = part_names.len() as u32;
+            }
+            "LINESTYLE" => {
+                // ["LINESTYLE","st1",颜色,线型,填充色,线宽]
+                let id = array.get(1).and_then(|v| v.as_str()).unwrap_or("").to_string();
+                let fill = array.get(4).map(|v| !v.is_null() && v.as_str() != Some("none")).unwrap_or(false);
+                let width = array.get(5).and_then(|v| v.as_f64()).unwrap_or(0.0);
+                line_styles.insert(id, (width, fill));
+            }
+            "FONTSTYLE" => {
+                // ["FONTSTYLE","st2",填充色,颜色,字体,字号,...]
+                let id = array.get(1).and_then(|v| v.as_str()).unwrap_or("").to_string();
+                if let Some(size) = array.get(5).and_then(|v| v.as_f64()) {
+                    font_styles.insert(id, size);
+                }
+            }
+            "PIN" => {
+                // ["PIN","e2",是否显示,电气类型,x,y,长度,旋转角度,颜色,引脚形状,...]
+                if array.len() < 8 {
+                    report.unconverted(line_number, type_name, "引脚数据长度不足");
+                    continue;
+                }
+                pins.push(EsymPin {
+                    line: line_number,
+                    unit: current_unit,
+                    id: array[1].as_str().unwrap_or("").to_string(),
+                    display: array[2].as_u64().map(|v| v != 0).or(array[2].as_bool()).unwrap_or(true),
+                    x: array[4].as_f64().unwrap_or(0.0),
+                    y: array[5].as_f64().unwrap_or(0.0),
+                    length: array[6].as_f64().unwrap_or(0.0),
+                    rotation: array[7].as_f64().unwrap_or(0.0),
+                    name: String::new(),
+                    number: String::new(),
+                    name_visible: true,
+                    number_visible: true,
+                    electric: esym_type_key(array.get(3)),
+                    shape: esym_type_key(array.get(9)),
+                });
+            }
+            "ATTR" => {
+                // ["ATTR","e3",所属ID,属性名,属性值,属性名是否显示,属性值是否显示,x,y,...]
+                let parent = array.get(2).and_then(|v| v.as_str());
+                let key = array.get(3).and_then(|v| v.as_str()).unwrap_or("").to_string();
+                let value = match array.get(4) {
+                    Some(Value::String(s)) => s.clone(),
+                    Some(Value::Number(n)) => n.to_string(),
+                    _ => String::new(),
+                };
+                let visible = array.get(6).and_then(|v| v.as_bool()).unwrap_or(true);
+                match parent {
+                    Some(parent) if !parent.is_empty() => pin_attrs.push((parent.to_string(), key, value, visible)),
+                    _ => {
+                        // 位号的显示位置
+                        if key == "Designator" {
+                            if let (Some(x), Some(y)) = (array.get(7).and_then(|v| v.as_f64()), array.get(8).and_then(|v| v.as_f64())) {
+                                designator_position = Some((x, y, visible));
+                            }
+                        }
+                        symbol_attrs.push((key, value));
+                    }
+                }
+            }
+            "RECT" | "POLY" | "CIRCLE" | "ARC" | "TEXT" => {
+                shape_lines.push((line_number, current_unit, array.clone()));
+            }
+            _ => {
+                report.unconverted(line_number, type_name, "暂不支持的类型");
+            }
+        }
+    }
+
+    // 引脚名称、编号
+    for (parent, key, value, visible) in pin_attrs {
+        if let Some(pin) = pins.iter_mut().find(|p| p.id == parent) {
+            match normalize_type_key(&key).as_str() {
+                "NAME" => {
+                    pin.name = value;
+                    pin.name_visible = visible;
+                }
+                "NUMBER" => {
+                    pin.number = value;
+                    pin.number_visible = visible;
+                }
+                // 引脚属性里的类型优先于PIN行里的
+                "PIN_TYPE" if !value.trim().is_empty() => pin.electric = Some(normalize_type_key(&value)),
+                "PIN_SHAPE" if !value.trim().is_empty() => pin.shape = Some(normalize_type_key(&value)),
+                _ => info!("引脚属性: {}={}", key, value),
+            }
+        }
+    }
+
+    // 位号前缀，如"U?"去掉问号
+    let reference = symbol_attrs.iter()
+        .find(|(key, _)| key == "Designator")
+        .map(|(_, value)| value.trim_end_matches('?').to_string())
+        .filter(|value| !value.is_empty())
+        .unwrap_or("U".to_string());
+    let symbol_name = symbol_title(symbol_title_str, &part_names);
+    if part_names.len() > 1 {
+        info!("多部件符号{}，共{}个单元", symbol_name, part_names.len());
+    }
+    report.title = symbol_name.to_string();
+    let mut kicad_sym_data = KicadSymData::new(symbol_name, &reference);
+    for (key, value) in &symbol_attrs {
+        if value.is_empty() {
+            continue;
+        }
+        if let Some((_, kicad_key)) = SYMBOL_ATTR_MAP.iter().find(|(easyeda_key, _)| easyeda_key == key) {
+            kicad_sym_data.set_property(kicad_key, value);
+        }
+    }
+    if let Some((x, y, visible)) = designator_position {
+        kicad_sym_data.set_property_position("Reference", x, y, visible);
+    }
+    // KiCad只能整体隐藏引脚名称、编号
+    if !pins.is_empty() && pins.iter().all(|p| !p.name_visible) {
+        kicad_sym_data.set_pin_names(1.016, true);
+    }
+    if !pins.is_empty() && pins.iter().all(|p| !p.number_visible) {
+        kicad_sym_data.set_pin_numbers_hide(true);
+    }
+
+    // 图形
+    let style_of = |v: Option<&Value>| -> (f64, bool) {
+        v.and_then(|v| v.as_str())
+            .and_then(|id| line_styles.get(id))
+            .cloned()
+            .unwrap_or((0.0, false))
+    };
+    for (line_number, unit, array) in &shape_lines {
+        let (line_number, unit) = (*line_number, *unit);
+        let type_name = array[0].as_str().unwrap_or("");
+        let num = |i: usize| array.get(i).and_then(|v| v.as_f64());
+        match type_name {
+            "RECT" => {
+                // ["RECT","e1",x1,y1,x2,y2,圆角x,圆角y,旋转角度,线型,...]
+                match (num(2), num(3), num(4), num(5)) {
+                    (Some(x1), Some(y1), Some(x2), Some(y2)) => {
+                        let (width, fill) = style_of(array.get(9));
+                        kicad_sym_data.add_rectangle(unit, x1, y1, x2, y2, width, fill);
+                    }
+                    _ => {
+                        report.unconverted(line_number, type_name, "矩形数据不完整");
+                    }
+                }
+            }
+            "POLY" => {
+                // ["POLY","e6",[x1,y1,x2,y2,...],是否闭合,线型,...]
+                let mut pts: Vec<f64> = array.get(2)
+                    .and_then(|v| v.as_array())
+                    .map(|arr| arr.iter().filter_map(|v| v.as_f64()).collect())
+                    .unwrap_or_default();
+                if pts.len() < 4 {
+                    report.unconverted(line_number, type_name, "折线点数不足");
+                    continue;
+                }
+                let closed = array.get(3).and_then(|v| v.as_bool()).unwrap_or(false);
+                if closed {
+                    pts.push(pts[0]);
+                    pts.push(pts[1]);
+                }
+                let (width, fill) = style_of(array.get(4));
+                kicad_sym_data.add_polyline(unit, &pts, width, fill && closed);
+            }
+            "CIRCLE" => {
+                // ["CIRCLE","e7",cx,cy,r,线型,...]
+                match (num(2), num(3), num(4)) {
+                    (Some(cx), Some(cy), Some(r)) => {
+                        let (width, fill) = style_of(array.get(5));
+                        kicad_sym_data.add_circle(unit, cx, cy, r, width, fill);
+                    }
+                    _ => {
+                        report.unconverted(line_number, type_name, "圆形数据不完整");
+                    }
+                }
+            }
+            "ARC" => {
+                // ["ARC","e8",起点x,起点y,中点x,中点y,终点x,终点y,线型,...]
+                match (num(2), num(3), num(4), num(5), num(6), num(7)) {
+                    (Some(x1), Some(y1), Some(x2), Some(y2), Some(x3), Some(y3)) => {
+                        let (width, _) = style_of(array.get(8));
+                        kicad_sym_data.add_arc(unit, x1, y1, x2, y2, x3, y3, width);
+                    }
+                    _ => {
+                        report.unconverted(line_number, type_name, "圆弧数据不完整");
+                    }
+                }
+            }
+            "TEXT" => {
+                // ["TEXT","e9",x,y,旋转角度,文本,字体,...]
+                let text = array.get(5).and_then(|v| v.as_str()).unwrap_or("");
+                let size = array.get(6)
+                    .and_then(|v| v.as_str())
+                    .and_then(|id| font_styles.get(id))
+                    .cloned();
+                kicad_sym_data.add_text(unit, text, num(2).unwrap_or(0.0), num(3).unwrap_or(0.0), num(4).unwrap_or(0.0), size);
+            }
+            _ => {}
+        }
+    }
+
+    // 引脚
+    for pin in &pins {
+        let pin_type = match pin.electric.as_deref() {
+            Some(key) => lookup_map(PIN_TYPE_MAP, key).unwrap_or_else(|| {
+                report.unconverted(pin.line, "PIN", format!("未知的引脚电气类型: {}", key));
+                "unspecified"
+            }),
+            None => "unspecified",
+        };
+        let pin_shape = match pin.shape.as_deref() {
+            Some(key) => lookup_map(PIN_SHAPE_MAP, key).unwrap_or_else(|| {
+                report.unconverted(pin.line, "PIN", format!("未知的引脚形状: {}", key));
+                "line"
+            }),
+            None => "line",
+        };
+        kicad_sym_data.add_pin(pin.unit, pin_type, pin_shape, &pin.name, &pin.number, pin.x, pin.y, pin.rotation, pin.length, pin.display);
+    }
+
+    info!("{}符号解析成功，共{}个单元，未完全解析{}处", symbol_name, part_names.len().max(1), report.unconverted.len());
+    ConvertedSymbol {
+        symbol: kicad_sym_data,
+        report,
+    }
+}
+
+// 定义一个efoo文件内容解析函数，device_attrs为JSON清单中使用该封装的器件的属性
+fn parse_efoo_file(efoo_content: &str, footprint_title_str: &str, device_attrs: &[(String, String)], options: &ConvertOptions) -> ConvertedFootprint {
+    let layer_map: HashMap<u64, &str> = LAYER_MAP.iter().cloned().collect();
+    let mut kicad_mod_data = KicadModData::new(footprint_title_str);
+    if options.polygon_arcs {
+        // fp_poly中的圆弧是KiCad 7的语法
+        kicad_mod_data.set_version(20221018);
+    }
+    // 封装自身的属性
+    let mut footprint_attrs: Vec<EfooAttr> = Vec::new();
+    // 焊盘和图形的外框，用于放置参考标识和值
+    let mut bounding_box = BoundingBox::new();
+    // 焊盘和过孔，过孔连接到所在的焊盘
+    let mut converted_pads: Vec<EfooPad> = Vec::new();
+    let mut vias: Vec<(usize, EfooVia)> = Vec::new();
+    // 转换结果
+    let mut report = ItemReport::new(ItemKind::Footprint, footprint_title_str);
+    for (line_index, line) in efoo_content.lines().enumerate() {
+        info!("当前行: {}", line);
+        let line_number = line_index + 1;
+
+        let primitive = match parse_efoo_line(line_index + 1, line) {
+            Ok(Some(primitive)) => primitive,
+            Ok(None) => {
+                info!("空行");
+                continue;
+            }
+            Err(e) => {
+                report.unconverted(e.line, &e.kind, e.message);
+                continue;
+            }
+        };
+
+        match primitive {
+            // 图形类
+            EfooPrimitive::Graphic { fill, layer, width, shapes } => {
+                let kind = if fill { "FILL" } else { "POLY" };
+                if fill {
+                    info!("填充类图形");
+                } else {
+                    info!("非填充类图形");
+                }
+                // 所在层
+                let layer: String = match layer_map.get(&layer) {
+                    Some(layer) => layer.to_string(),
+                    None => {
+                        report.unconverted(line_number, kind, format!("未知的层: {}", layer));
+                        continue;
+                    }
+                };
+                info!("所在层: {}", layer);
+                for shape in &shapes {
+                    bounding_box.include_shape(shape, width / 2.0);
+                }
+                if layer == "F&B.Cu *.Mask" {
+                    // 多层上的图形是非金属化孔，圆和长圆形输出为钻孔，其它形状输出为Edge.Cuts上的开槽轮廓
+                    for shape in shapes {
+                        if let Some((x, y, angle, drill_x, drill_y)) = hole_shape(&shape) {
+                            info!("非金属化孔");
+                            kicad_mod_data.add_pad_np_hole("", x, y, angle, drill_x, drill_y);
+                            continue;
+                        }
+                        let path = match shape {
+                            EfooShape::Path(path) => path,
+                            EfooShape::Rect { x, y, width: w, height: h, rotation, radius } => rect_path(x, y, w, h, rotation, radius),
+                            EfooShape::Circle { .. } => continue,
+                            EfooShape::Unsupported(description) => {
+                                report.unconverted(line_number, kind, description);
+                                continue;
+                            }
+                        };
+                        report.warn(format!("第{}行{}: 非圆形、长圆形的孔无法钻孔，输出为Edge.Cuts上的开槽", line_number, kind));
+                        // 填充图形的线宽通常为0，Edge.Cuts上使用0.1mm
+                        let edge_width = if width > 0.0 { width } else { 0.1 / 0.0254 };
+                        add_outline(&mut kicad_mod_data, &path, "Edge.Cuts", edge_width);
+                    }
+                    continue;
+                }
+                // 填充图形有多个轮廓时，被奇数个轮廓包含的轮廓是孔，孔用切口合并到包含它的外轮廓中
+                let contours: Vec<Option<Vec<(f64, f64)>>> = if fill && shapes.len() > 1 {
+                    shapes.iter().map(|shape| shape_points(shape, options.chord_tolerance)).collect()
+                } else {
+                    Vec::new()
+                };
+                let (is_hole, holes, orphans) = contour_holes(&contours);
+                for index in orphans {
+                    report.warn(format!("第{}行{}: 第{}个轮廓是孔，但找不到包含它的外轮廓，单独输出为多边形", line_number, kind, index + 1));
+                }
+                for (index, shape) in shapes.into_iter().enumerate() {
+                    if is_hole.get(index).copied().unwrap_or(false) {
+                        continue;
+                    }
+                    if let (Some(Some(outer)), Some(hole_indexes)) = (contours.get(index), holes.get(index)) {
+                        if !hole_indexes.is_empty() {
+                            info!("带孔的填充图形，共{}个孔", hole_indexes.len());
+                            let hole_points: Vec<&[(f64, f64)]> = hole_indexes.iter()
+                                .filter_map(|hole| contours[*hole].as_deref())
+                                .collect();
+                            let pts = keyhole_points(outer, &hole_points);
+                            kicad_mod_data.add_graphic_element_polygon(&pts, layer.clone(), width, fill);
+                            continue;
+                        }
+                    }
+                    // 旋转或带圆角的矩形按路径处理
+                    let shape = match shape {
+                        EfooShape::Rect { x, y, width: w, height: h, rotation, radius } if normalize_angle(rotation) != 0.0 || radius > 0.0 => {
+                            EfooShape::Path(rect_path(x, y, w, h, rotation, radius))
+                        }
+                        shape => shape,
+                    };
+                    match shape {
+                        EfooShape::Rect { x, y, width: w, height: h, .. } => {
+                            info!("矩形");
+                            kicad_mod_data.add_graphic_element_rect(x, y, x + w, y - h, layer.clone(), width, fill);
+                        }
+                        EfooShape::Circle { center_x, center_y, radius } => {
+                            // 圆，结束点在圆心正上方
+                            info!("圆形");
+                            kicad_mod_data.add_graphic_element_circle(center_x, center_y, center_x, center_y + radius, layer.clone(), width, fill);
+                        }
+                        EfooShape::Path(path) => {
+                            if !fill {
+                                // 轮廓逐段输出为直线和圆弧
+                                info!("多边形、圆弧组合轮廓");
+                                add_outline(&mut kicad_mod_data, &path, &layer, width);
+                            } else if options.polygon_arcs {
+                                info!("带圆弧的填充多边形");
+                                let segments: Vec<OutlineSegment> = path.segments.iter().map(|segment| match *segment {
+                                    EfooSegment::Line { x, y } => OutlineSegment::Line { x, y },
+                                    EfooSegment::Arc { angle, x, y } => OutlineSegment::Arc { angle, x, y },
+                                }).collect();
+                                kicad_mod_data.add_graphic_element_polygon_arcs(path.start_x, path.start_y, &segments, layer.clone(), width, fill);
+                            } else {
+                                info!("填充多边形，圆弧拟合为线段");
+                                let pts = path_to_points(&path, options.chord_tolerance);
+                                kicad_mod_data.add_graphic_element_polygon(&pts, layer.clone(), width, fill);
+                            }
+                        }
+                        EfooShape::Unsupported(description) => {
+                            report.unconverted(line_number, kind, description);
+                        }
+                    }
+                }
+            }
+            EfooPrimitive::Pad(pad) => {
+                info!("焊盘");
+                let pad_name = pad.number.as_str();
+                let placement = match pad_placement(&pad) {
+                    Ok(placement) => placement,
+                    Err(e) => {
+                        report.unconverted(line_number, "PAD", e);
+                        continue;
+                    }
+                };
+                if let Some(message) = &placement.warning {
+                    report.warn(format!("第{}行PAD: {}", line_number, message));
+                }
+                let PadPlacement { x: center_x, y: center_y, rotation: angle, .. } = placement;
+                let geometry = |width: f64, height: f64| PadGeometry::new(center_x, center_y, angle, width, height);
+                // 是否输出了带铜皮的焊盘
+                let emitted = match placement.drill {
+                    // 通孔，圆孔时钻孔宽高相同
+                    Some(drill) => {
+                        let (drill_x, drill_y) = (drill.width, drill.height);
+                        let slot = drill_x != drill_y;
+                        info!("通孔焊盘，{}", if slot { "槽孔" } else { "圆孔" });
+                        if !pad.plated || pad_without_copper(&pad.shape, drill_x, drill_y) {
+                            info!("非金属化焊盘或焊盘不大于钻孔，输出为非金属化孔");
+                            if !pad.plated && !pad_without_copper(&pad.shape, drill_x, drill_y) {
+                                report.warn(format!("第{}行PAD: 非金属化焊盘输出为非金属化孔，孔外的铜皮未转换", line_number));
+                            }
+                            // 非金属化孔没有铜皮，直接把孔移到偏移后的位置
+                            let (sin, cos) = angle.to_radians().sin_cos();
+                            let hole_x = center_x + drill.offset_x * cos - drill.offset_y * sin;
+                            let hole_y = center_y + drill.offset_x * sin + drill.offset_y * cos;
+                            kicad_mod_data.add_pad_np_hole(pad_name, hole_x, hole_y, angle, drill_x, drill_y);
+                            // 没有铜皮，过孔不连接到该焊盘
+                            bounding_box.include_pad(&pad);
+                            continue;
+                        }
+                        match &pad.shape {
+                            EfooPadShape::Ellipse { width, height } if width == height => {
+                                kicad_mod_data.add_pad_hole(pad_name, &geometry(*width, *width), &drill);
+                                true
+                            }
+                            // KiCad没有椭圆焊盘，用长圆形代替
+                            EfooPadShape::Ellipse { width, height } | EfooPadShape::Oval { width, height } => {
+                                kicad_mod_data.add_pad_hole_oval(pad_name, &geometry(*width, *height), &drill);
+                                true
+                            }
+                            EfooPadShape::Rect { width, height, radius } if *radius > 0.0 => {
+                                let rratio = roundrect_rratio(*radius);
+                                kicad_mod_data.add_pad_hole_roundrect(pad_name, &geometry(*width, *height), rratio, &drill);
+                                true
+                            }
+                            EfooPadShape::Rect { width, height, .. } => {
+                                kicad_mod_data.add_pad_hole_rect(pad_name, &geometry(*width, *height), &drill);
+                                true
+                            }
+                            EfooPadShape::Poly(path) => {
+                                info!("通孔的多边形焊盘");
+                                let pts = path_to_points(path, options.chord_tolerance);
+                                // 锚点焊盘与钻孔的较小边一样大
+                                let anchor_size = drill_x.min(drill_y);
+                                kicad_mod_data.add_pad_hole_poly(pad_name, &geometry(anchor_size, anchor_size), &pts, &drill);
+                                true
+                            }
+                            EfooPadShape::Unknown(kind) => {
+                                report.unconverted(line_number, "PAD", format!("未知的焊盘形状: {}", kind));
+                                false
+                            }
+                        }
+                    }
+                    // 贴片
+                    None => {
+                        info!("贴片焊盘");
+                        let layers = match smd_pad_layers(pad.layer) {
+                            Some(layers) => layers,
+                            None => {
+                                report.unconverted(line_number, "PAD", format!("贴片焊盘的层未知: {}", pad.layer));
+                                continue;
+                            }
+                        };
+                        let margins = PadMargins { solder_mask: pad.solder_mask_margin, solder_paste: pad.solder_paste_margin };
+                        match &pad.shape {
+                            EfooPadShape::Ellipse { width, height } if width == height => {
+                                info!("圆形焊盘");
+                                kicad_mod_data.add_pad_circle(pad_name, &geometry(*width, *width), &layers, &margins);
+                                true
+                            }
+                            // KiCad没有椭圆焊盘，用长圆形代替
+                            EfooPadShape::Ellipse { width, height } | EfooPadShape::Oval { width, height } => {
+                                info!("椭圆焊盘");
+                                kicad_mod_data.add_pad_ellipse(pad_name, &geometry(*width, *height), &layers, &margins);
+                                true
+                            }
+                            EfooPadShape::Rect { width, height, radius } if *radius > 0.0 => {
+                                info!("圆角矩形焊盘");
+                                let rratio = roundrect_rratio(*radius);
+                                kicad_mod_data.add_pad_roundrect(pad_name, &geometry(*width, *height), rratio, &layers, &margins);
+                                true
+                            }
+                            EfooPadShape::Rect { width, height, .. } => {
+                                info!("矩形焊盘");
+                                kicad_mod_data.add_pad_rect(pad_name, &geometry(*width, *height), &layers, &margins);
+                                true
+                            }
+                            EfooPadShape::Poly(path) => {
+                                if let Some(chamfer) = chamfered_rect(path, center_x, center_y, angle) {
+                                    info!("切角矩形焊盘");
+                                    kicad_mod_data.add_pad_chamfered_rect(pad_name, &geometry(chamfer.width, chamfer.height), chamfer.ratio, &chamfer.corners, &layers, &margins);
+                                } else {
+                                    info!("多边形焊盘");
+                                    let pts = path_to_points(path, options.chord_tolerance);
+                                    kicad_mod_data.add_pad_poly(pad_name, center_x, center_y, &pts, &layers, &margins);
+                                }
+                                true
+                            }
+                            EfooPadShape::Unknown(kind) => {
+                                report.unconverted(line_number, "PAD", format!("未知的焊盘形状: {}", kind));
+                                false
+                            }
+                        }
+                    }
+                };
+                // 只记录输出了的焊盘，过孔不会连接到不存在的焊盘
+                if emitted {
+                    bounding_box.include_pad(&pad);
+                    converted_pads.push(pad);
+                }
+            }
+            // 过孔在所有焊盘之后处理，以便找到所在的焊盘
+            EfooPrimitive::Via(via) => vias.push((line_number, via)),
+            EfooPrimitive::String(string) => {
+                info!("文本: {}", string.text);
+                if string.text.trim().is_empty() {
+                    continue;
+                }
+                let layer = match layer_map.get(&string.layer) {
+                    Some(layer) if *layer != "F&B.Cu *.Mask" => *layer,
+                    _ => {
+                        report.unconverted(line_number, "STRING", format!("文本不能放在该层: {}", string.layer));
+                        continue;
+                    }
+                };
+                if !string.font.is_empty() && string.font != "default" {
+                    report.warn(format!("第{}行文本的字体{}替换为KiCad默认字体", line_number, string.font));
+                }
+                // 笔画宽度为0时使用字高的15%
+                let thickness = if string.stroke_width > 0.0 { string.stroke_width } else { string.height * 0.15 };
+                kicad_mod_data.add_text(&string.text, string.x, string.y, normalize_angle(string.rotation), layer, string.height, thickness, &text_justify(string.align), string.mirror);
+            }
+            EfooPrimitive::Region(region) => {
+                info!("区域");
+                let layers = match region_layers(region.layer) {
+                    Some(layers) => layers,
+                    None => {
+                        report.unconverted(line_number, "REGION", format!("禁止区域不能放在该层: {}", region.layer));
+                        continue;
+                    }
+                };
+                let mut not_allowed: Vec<&str> = Vec::new();
+                for rule in &region.rules {
+                    match REGION_RULE_MAP.iter().find(|(number, _)| number == rule) {
+                        Some((_, kind)) => {
+                            if !not_allowed.contains(kind) {
+                                not_allowed.push(kind);
+                            }
+                        }
+                        None => report.warn(format!("第{}行区域的禁止类型{}无法转换", line_number, rule)),
+                    }
+                }
+                if not_allowed.is_empty() {
+                    report.unconverted(line_number, "REGION", "没有可转换的禁止类型");
+                    continue;
+                }
+                for shape in &region.shapes {
+                    let pts = match shape {
+                        EfooShape::Circle { center_x, center_y, radius } => path_to_points(&circle_path(*center_x, *center_y, *radius), options.chord_tolerance),
+                        EfooShape::Path(path) => path_to_points(path, options.chord_tolerance),
+                        EfooShape::Rect { x, y, width, height, rotation, radius } => path_to_points(&rect_path(*x, *y, *width, *height, *rotation, *radius), options.chord_tolerance),
+                        EfooShape::Unsupported(message) => {
+                            report.unconverted(line_number, "REGION", message.clone());
+                            continue;
+                        }
+                    };
+                    kicad_mod_data.add_keepout_zone(&layers, &pts, &not_allowed);
+                }
+            }
+            EfooPrimitive::Attr(attr) => {
+                info!("属性: {}={}", attr.key, attr.value);
+                if attr.parent.is_empty() {
+                    footprint_attrs.push(attr);
+                }
+            }
+            EfooPrimitive::Other(type_name) => {
+                if NON_GEOMETRY_TYPES.contains(&type_name.as_str()) {
+                    info!("其它类型: {}", type_name);
+                } else {
+                    report.unconverted(line_number, &type_name, "暂不支持的类型");
+                }
+            }
+        }
+    }
+
+    // 过孔，在焊盘内时使用该焊盘的编号，否则不连接任何焊盘
+    for (line_number, via) in vias {
+        if via.drill <= 0.0 || via.diameter <= via.drill {
+            report.unconverted(line_number, "VIA", format!("外径{}不大于钻孔直径{}", via.diameter, via.drill));
+            continue;
+        }
+        let pad_name = converted_pads.iter()
+            .find(|pad| pad_contains(pad, via.x, via.y))
+            .map(|pad| pad.number.as_str())
+            .unwrap_or("");
+        bounding_box.include(via.x, via.y, via.diameter / 2.0);
+        kicad_mod_data.add_pad_via(pad_name, via.x, via.y, via.diameter, via.drill);
+    }
+
+    // 器件属性在前，封装自身的属性优先
+    let attrs = device_attrs.iter()
+        .map(|(key, value)| (key.as_str(), value.as_str()))
+        .chain(footprint_attrs.iter().map(|attr| (attr.key.as_str(), attr.value.as_str())));
+    for (key, value) in attrs {
+        match footprint_flag(key, value) {
+            Some(FootprintFlag::ExcludeFromPosFiles(exclude)) => kicad_mod_data.set_exclude_from_pos_files(exclude),
+            Some(FootprintFlag::ExcludeFromBom(exclude)) => kicad_mod_data.set_exclude_from_bom(exclude),
+            None => {}
+        }
+    }
+
+    // 参考标识放在位号文本处，值放在名称文本处，没有时分别放在外框的上方和下方
+    let placed_attr = |key: &str| {
+        footprint_attrs.iter()
+            .find(|attr| attr.key == key && attr.x.is_some() && attr.y.is_some())
+    };
+    let field_position = |attr: &EfooAttr| (attr.x.unwrap_or(0.0), attr.y.unwrap_or(0.0), normalize_angle(attr.rotation), attr.height, attr.stroke_width);
+    let (center_x, _) = bounding_box.center();
+    match placed_attr("Designator") {
+        Some(attr) => {
+            let (x, y, angle, height, thickness) = field_position(attr);
+            let (height, thickness) = field_text_size(height, thickness);
+            kicad_mod_data.set_reference_position(x, y, angle, height, thickness);
+            // 参考标识只能放在丝印层或装配层
+            let layer = match layer_map.get(&attr.layer) {
+                Some(layer) if layer.ends_with(".SilkS") || layer.ends_with(".Fab") => *layer,
+                _ => {
+                    report.warn(format!("位号不能放在层{}，参考标识放在F.SilkS", attr.layer));
+                    "F.SilkS"
+                }
+            };
+            kicad_mod_data.set_reference_layer(layer, !attr.visible);
+        }
+        None if !bounding_box.is_empty() => {
+            kicad_mod_data.set_reference_position(center_x, bounding_box.max_y + FIELD_OFFSET, 0.0, FIELD_HEIGHT, FIELD_THICKNESS);
+        }
+        None => {}
+    }
+    match placed_attr("Name") {
+        Some(attr) => {
+            let (x, y, angle, height, thickness) = field_position(attr);
+            let (height, thickness) = field_text_size(height, thickness);
+            kicad_mod_data.set_value_position(x, y, angle, height, thickness);
+            // 值始终放在装配层，名称在底层时放在B.Fab
+            if matches!(layer_map.get(&attr.layer), Some(layer) if layer.starts_with("B.")) {
+                kicad_mod_data.set_value_layer("B.Fab");
+            }
+        }
+        None if !bounding_box.is_empty() => {
+            kicad_mod_data.set_value_position(center_x, bounding_box.min_y - FIELD_OFFSET, 0.0, FIELD_HEIGHT, FIELD_THICKNESS);
+        }
+        None => {}
+    }
+
+    info!("{}解析成功，未完全解析{}处", footprint_title_str, report.unconverted.len());
+    ConvertedFootprint {
+        content: kicad_mod_data.generate_content(),
+        report,
+    }
+}
+
+// 参考标识和值的默认字高1mm、笔画宽度0.15mm，与外框的距离为字高，单位为mil
+const FIELD_HEIGHT: f64 = 1.0 / 0.0254;
+const FIELD_THICKNESS: f64 = 0.15 / 0.0254;
+const FIELD_OFFSET: f64 = FIELD_HEIGHT;
+
+// 属性文本的字高和笔画宽度，为0时使用默认值
+fn field_text_size(height: f64, thickness: f64) -> (f64, f64) {
+    let height = if height > 0.0 { height } else { FIELD_HEIGHT };
+    let thickness = if thickness > 0.0 { thickness } else { height * 0.15 };
+    (height, thickness)
+}
+
+// 外框，单位为mil，Y轴向上
+struct BoundingBox {
+    min_x: f64,
+    min_y: f64,
+    max_x: f64,
+    max_y: f64,
+}
+
+impl BoundingBox {
+    fn new() -> Self {
+        BoundingBox { min_x: f64::INFINITY, min_y: f64::INFINITY, max_x: f64::NEG_INFINITY, max_y: f64::NEG_INFINITY }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.min_x > self.max_x
+    }
+
+    fn center(&self) -> (f64, f64) {
+        ((self.min_x + self.max_x) / 2.0, (self.min_y + self.max_y) / 2.0)
+    }
+
+    // 加入以(x, y)为中心、半径为margin的范围
+    fn include(&mut self, x: f64, y: f64, margin: f64) {
+        self.min_x = self.min_x.min(x - margin);
+        self.min_y = self.min_y.min(y - margin);
+        self.max_x = self.max_x.max(x + margin);
+        self.max_y = self.max_y.max(y + margin);
+    }
+
+    // 路径只加入各段的端点，圆弧的外凸部分忽略
+    fn include_path(&mut self, path: &EfooPath, margin: f64) {
+        self.include(path.start_x, path.start_y, margin);
+        for segment in &path.segments {
+            match *segment {
+                EfooSegment::Line { x, y } | EfooSegment::Arc { x, y, .. } => self.include(x, y, margin),
+            }
+        }
+    }
+
+    fn include_shape(&mut self, shape: &EfooShape, margin: f64) {
+        match shape {
+            EfooShape::Circle { center_x, center_y, radius } => self.include(*center_x, *center_y, radius + margin),
+            EfooShape::Path(path) => self.include_path(path, margin),
+            EfooShape::Rect { x, y, width, height, rotation, radius } => self.include_path(&rect_path(*x, *y, *width, *height, *rotation, *radius), margin),
+            EfooShape::Unsupported(_) => {}
+        }
+    }
+
+    // 焊盘按长边的一半向四周扩展，不考虑旋转
+    fn include_pad(&mut self, pad: &EfooPad) {
+        match &pad.shape {
+            EfooPadShape::Ellipse { width, height }
+            | EfooPadShape::Rect { width, height, .. }
+            | EfooPadShape::Oval { width, height } => self.include(pad.x, pad.y, width.max(*height) / 2.0),
+            EfooPadShape::Poly(path) => self.include_path(path, 0.0),
+            EfooPadShape::Unknown(_) => self.include(pad.x, pad.y, 0.0),
+        }
+    }
+}
+
+// 文本对齐方式，EasyEDA的1~9依次为左上、中上、右上、左中、中心、右中、左下、中下、右下
+fn text_justify(align: u64) -> Vec<&'static str> {
+    let mut justify = Vec::new();
+    match align {
+        1 | 4 | 7 => justify.push("left"),
+        3 | 6 | 9 => justify.push("right"),
+        _ => {}
+    }
+    match align {
+        1..=3 => justify.push("top"),
+        7..=9 => justify.push("bottom"),
+        _ => {}
+    }
+    justify
+}
+
+// EasyEDA矩形焊盘的圆角半径为与短边之比的百分比，50%时短边两端为半圆
+// KiCad的roundrect_rratio同样是圆角半径与短边之比，最大0.5
+fn roundrect_rratio(radius: f64) -> f64 {
+    (radius / 100.0).clamp(0.0, 0.5)
+}
+
+// 切角矩形
+struct ChamferedRect {
+    width: f64,
+    height: f64,
+    /// 切角长度与短边之比
+    ratio: f64,
+    /// 切角的位置，KiCad坐标系(Y轴向下)中的上下左右
+    corners: Vec<&'static str>,
+}
+
+// 判断多边形焊盘是否为以焊盘中心为中心的切角矩形，所有切角的长度必须相同。
+// 多边形的坐标为绝对坐标，先转到焊盘自身的坐标系，得到的宽高和切角按焊盘的角度旋转
+fn chamfered_rect(path: &EfooPath, center_x: f64, center_y: f64, rotation: f64) -> Option<ChamferedRect> {
+    const EPSILON: f64 = 1e-6;
+    let (sin, cos) = (-rotation).to_radians().sin_cos();
+    let local = |x: f64, y: f64| {
+        let (dx, dy) = (x - center_x, y - center_y);
+        (dx * cos - dy * sin, dx * sin + dy * cos)
+    };
+    let mut pts = vec![local(path.start_x, path.start_y)];
+    for segment in &path.segments {
+        match *segment {
+            EfooSegment::Line { x, y } => pts.push(local(x, y)),
+            EfooSegment::Arc { .. } => return None,
+        }
+    }
+    // 去掉与起点重合的终点
+    if pts.len() > 1 && pts.first().zip(pts.last()).is_some_and(|(a, b)| (a.0 - b.0).hypot(a.1 - b.1) < EPSILON) {
+        pts.pop();
+    }
+    if !(5..=8).contains(&pts.len()) {
+        return None;
+    }
+
+    let min_x = pts.iter().map(|p| p.0).fold(f64::INFINITY, f64::min);
+    let max_x = pts.iter().map(|p| p.0).fold(f64::NEG_INFINITY, f64::max);
+    let min_y = pts.iter().map(|p| p.1).fold(f64::INFINITY, f64::min);
+    let max_y = pts.iter().map(|p| p.1).fold(f64::NEG_INFINITY, f64::max);
+    let (width, height) = (max_x - min_x, max_y - min_y);
+    if width < EPSILON || height < EPSILON
+        || ((min_x + max_x) / 2.0).abs() > EPSILON
+        || ((min_y + max_y) / 2.0).abs() > EPSILON
+    {
+        return None;
+    }
+    let has = |x: f64, y: f64| pts.iter().any(|p| (p.0 - x).abs() < EPSILON && (p.1 - y).abs() < EPSILON);
+
+    // 逐个角检查：角本身是顶点，或两条边上距离角相同的两个点是顶点
+    // EasyEDA的Y轴向上，max_y对应KiCad的top
+    let corner_list = [
+        (min_x, max_y, 1.0, -1.0, "top_left"),
+        (max_x, max_y, -1.0, -1.0, "top_right"),
+        (min_x, min_y, 1.0, 1.0, "bottom_left"),
+        (max_x, min_y, -1.0, 1.0, "bottom_right"),
+    ];
+    let mut chamfer: Option<f64> = None;
+    let mut corners = Vec::new();
+    let mut vertex_count = 0;
+    for (x, y, dx, dy, name) in corner_list {
+        if has(x, y) {
+            vertex_count += 1;
+            continue;
+        }
+        // 水平边上离角最近的点
+        let size = pts
+            .iter()
+            .filter(|p| (p.1 - y).abs() < EPSILON)
+            .map(|p| (p.0 - x).abs())
+            .fold(f64::INFINITY, f64::min);
+        if !size.is_finite() || size < EPSILON || !has(x, y + dy * size) || !has(x + dx * size, y) {
+            return None;
+        }
+        match chamfer {
+            Some(c) if (c - size).abs() > EPSILON => return None,
+            _ => chamfer = Some(size),
+        }
+        vertex_count += 2;
+        corners.push(name);
+    }
+    let ratio = chamfer? / width.min(height);
+    if vertex_count != pts.len() || ratio > 0.5 + EPSILON {
+        return None;
+    }
+    Some(ChamferedRect {
+        width,
+        height,
+        ratio: ratio.min(0.5),
+        corners,
+    })
+}
+
+// 焊盘在KiCad中的位置、角度和钻孔
+//
+// EasyEDA与KiCad的焊盘定义方式相同：形状的宽高是未旋转时X、Y方向的尺寸，整个焊盘再绕中心逆时针旋转rotation度，
+// 所以宽高不需要交换，角度直接作为KiCad的(at x y rot)。
+// KiCad的钻孔跟随焊盘旋转，不能单独旋转，EasyEDA中钻孔相对焊盘的旋转只能换算为90度的倍数：
+// 转90度、270度时交换槽孔的宽高
+struct PadPlacement {
+    /// 中心坐标，EasyEDA坐标，由KicadModData转换为KiCad坐标
+    x: f64,
+    y: f64,
+    /// 旋转角度，范围[0, 360)
+    rotation: f64,
+    /// 钻孔在焊盘坐标系中的宽高和偏移，贴片焊盘为None
+    drill: Option<PadDrill>,
+    /// 无法精确转换时的说明
+    warning: Option<String>,
+}
+
+// 把角度规范到[0, 360)，并去掉浮点误差
+fn normalize_angle(angle: f64) -> f64 {
+    let angle = (angle.rem_euclid(360.0) * 1e6).round() / 1e6;
+    if angle >= 360.0 {
+        0.0
+    } else {
+        angle
+    }
+}
+
+// 判断点是否在焊盘内，多边形焊盘的圆弧按弦处理
+fn pad_contains(pad: &EfooPad, x: f64, y: f64) -> bool {
+    // 转到焊盘自身的坐标系
+    let (sin, cos) = (-pad.rotation).to_radians().sin_cos();
+    let (dx, dy) = (x - pad.x, y - pad.y);
+    let (local_x, local_y) = (dx * cos - dy * sin, dx * sin + dy * cos);
+    match &pad.shape {
+        EfooPadShape::Ellipse { width, height } => {
+            let (a, b) = (width / 2.0, height / 2.0);
+            a > 0.0 && b > 0.0 && (local_x / a).powi(2) + (local_y / b).powi(2) <= 1.0
+        }
+        EfooPadShape::Rect { width, height, .. } | EfooPadShape::Oval { width, height } => {
+            local_x.abs() <= width / 2.0 && local_y.abs() <= height / 2.0
+        }
+        // 多边形的坐标为绝对坐标
+        EfooPadShape::Poly(path) => {
+            let mut pts = vec![(path.start_x, path.start_y)];
+            for segment in &path.segments {
+                match *segment {
+                    EfooSegment::Line { x, y } | EfooSegment::Arc { x, y, .. } => pts.push((x, y)),
+                }
+            }
+            point_in_polygon(&pts, x, y)
+        }
+        EfooPadShape::Unknown(_) => false,
+    }
+}
+
+// 计算焊盘的位置、角度和钻孔，所有焊盘类型统一使用
+fn pad_placement(pad: &EfooPad) -> Result<PadPlacement, String> {
+    let mut warning = None;
+    let size = match &pad.hole {
+        None => None,
+        Some(EfooHole::Round { width, .. }) => Some((*width, *width)),
+        Some(EfooHole::Slot { width, height }) => {
+            let hole_rotation = normalize_angle(pad.hole_rotation);
+            // 离钻孔相对角度最近的90度倍数
+            let quarter_turns = (hole_rotation / 90.0).round();
+            if (hole_rotation - quarter_turns * 90.0).abs() > 1e-6 {
+                warning = Some(format!("槽孔相对焊盘旋转{}度，KiCad只支持90度的倍数，已取{}度", hole_rotation, quarter_turns * 90.0));
+            }
+            if quarter_turns as i64 % 2 == 1 {
+                Some((*height, *width))
+            } else {
+                Some((*width, *height))
+            }
+        }
+        Some(EfooHole::Unknown(kind)) => return Err(format!("未知的钻孔形状: {}", kind)),
+    };
+    // 钻孔偏移在焊盘坐标系中，与KiCad的(offset x y)一致
+    let drill = size.map(|(width, height)| PadDrill {
+        width,
+        height,
+        offset_x: pad.hole_offset_x,
+        offset_y: pad.hole_offset_y,
+    });
+    Ok(PadPlacement {
+        x: pad.x,
+        y: pad.y,
+        rotation: normalize_angle(pad.rotation),
+        drill,
+        warning,
+    })
+}
+
+// 射线法判断点是否在多边形内
+fn point_in_polygon(pts: &[(f64, f64)], x: f64, y: f64) -> bool {
+    let mut inside = false;
+    for i in 0..pts.len() {
+        let (x1, y1) = pts[i];
+        let (x2, y2) = pts[(i + 1) % pts.len()];
+        if (y1 > y) != (y2 > y) && x < x1 + (y - y1) * (x2 - x1) / (y2 - y1) {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+// 多边形的有向面积，逆时针为正
+fn signed_area(pts: &[(f64, f64)]) -> f64 {
+    let mut area = 0.0;
+    for i in 0..pts.len() {
+        let (x1, y1) = pts[i];
+        let (x2, y2) = pts[(i + 1) % pts.len()];
+        area += x1 * y2 - x2 * y1;
+    }
+    area / 2.0
+}
+
+// 把轮廓转换为点，圆弧按弦高误差拟合为线段，去掉与起点重合的终点，不支持的图形返回None
+fn shape_points(shape: &EfooShape, chord_tolerance: f64) -> Option<Vec<(f64, f64)>> {
+    let flat = match shape {
+        EfooShape::Circle { center_x, center_y, radius } => path_to_points(&circle_path(*center_x, *center_y, *radius), chord_tolerance),
+        EfooShape::Path(path) => path_to_points(path, chord_tolerance),
+        EfooShape::Rect { x, y, width, height, rotation, radius } => path_to_points(&rect_path(*x, *y, *width, *height, *rotation, *radius), chord_tolerance),
+        EfooShape::Unsupported(_) => return None,
+    };
+    let mut pts: Vec<(f64, f64)> = flat.chunks_exact(2).map(|p| (p[0], p[1])).collect();
+    if pts.len() > 1 {
+        let (first, last) = (pts[0], pts[pts.len() - 1]);
+        if (first.0 - last.0).abs() < 1e-6 && (first.1 - last.1).abs() < 1e-6 {
+            pts.pop();
+        }
+    }
+    Some(pts)
+}
+
+// 确定各轮廓之间的包含关系，被奇数个轮廓包含的是孔，孔属于比它少一层的包含轮廓
+// 返回每个轮廓是否为孔、每个外轮廓的孔的序号，以及找不到外轮廓的孔的序号，这些孔按外轮廓处理
+fn contour_holes(contours: &[Option<Vec<(f64, f64)>>]) -> (Vec<bool>, Vec<Vec<usize>>, Vec<usize>) {
+    // 包含每个轮廓的其它轮廓，用轮廓的第一个点判断
+    let containers: Vec<Vec<usize>> = contours.iter().enumerate()
+        .map(|(i, contour)| match contour.as_ref().and_then(|pts| pts.first()) {
+            Some(&(x, y)) => contours.iter().enumerate()
+                .filter(|(j, other)| *j != i && other.as_ref().is_some_and(|pts| pts.len() >= 3 && point_in_polygon(pts, x, y)))
+                .map(|(j, _)| j)
+                .collect(),
+            None => Vec::new(),
+        })
+        .collect();
+    let mut is_hole = vec![false; contours.len()];
+    let mut holes = vec![Vec::new(); contours.len()];
+    let mut orphans = Vec::new();
+    for (i, container) in containers.iter().enumerate() {
+        if container.len() % 2 == 0 {
+            continue;
+        }
+        match container.iter().find(|&&j| containers[j].len() + 1 == container.len()) {
+            Some(&parent) => {
+                is_hole[i] = true;
+                holes[parent].push(i);
+            }
+            None => orphans.push(i),
+        }
+    }
+    (is_hole, holes, orphans)
+}
+
+// 用切口把孔合并到外轮廓中，得到一个多边形，格式为[x1, y1, x2, y2, ...]
+// 外轮廓取逆时针、孔取顺时针，孔按最右点从右到左依次合并，每个孔从最右点连到外轮廓上可见的顶点，再沿原路返回
+fn keyhole_points(outer: &[(f64, f64)], holes: &[&[(f64, f64)]]) -> Vec<f64> {
+    let mut result = outer.to_vec();
+    if signed_area(&result) < 0.0 {
+        result.reverse();
+    }
+    let max_x = |pts: &[(f64, f64)]| pts.iter().map(|p| p.0).fold(f64::NEG_INFINITY, f64::max);
+    let mut holes: Vec<&[(f64, f64)]> = holes.iter().copied().filter(|hole| !hole.is_empty()).collect();
+    holes.sort_by(|a, b| max_x(b).total_cmp(&max_x(a)));
+    for hole in holes {
+        let mut hole = hole.to_vec();
+        if signed_area(&hole) > 0.0 {
+            hole.reverse();
+        }
+        let hole_start = (0..hole.len())
+            .max_by(|&a, &b| hole[a].0.total_cmp(&hole[b].0))
+            .unwrap_or(0);
+        let start = hole[hole_start];
+        let outer_index = visible_vertex(&result, start);
+        let mut bridge: Vec<(f64, f64)> = hole[hole_start..].iter().chain(hole[..hole_start].iter()).copied().collect();
+        bridge.push(start);
+        bridge.push(result[outer_index]);
+        result.splice(outer_index + 1..outer_index + 1, bridge);
+    }
+    result.into_iter().flat_map(|(x, y)| [x, y]).collect()
+}
+
+// 找到逆时针多边形上从点m可见的顶点：从m向右作射线，取最近的交点所在的边上X较大的端点，
+// 若m、交点和该端点组成的三角形内有凹顶点，改为其中与射线夹角最小的凹顶点
+fn visible_vertex(polygon: &[(f64, f64)], m: (f64, f64)) -> usize {
+    const EPSILON: f64 = 1e-9;
+    let n = polygon.len();
+    let cross = |o: (f64, f64), a: (f64, f64), b: (f64, f64)| (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0);
+    let mut nearest: Option<(f64, usize)> = None;
+    for i in 0..n {
+        let (a, b) = (polygon[i], polygon[(i + 1) % n]);
+        if (a.1 > m.1) == (b.1 > m.1) {
+            continue;
+        }
+        let x = a.0 + (m.1 - a.1) * (b.0 - a.0) / (b.1 - a.1);
+        if x >= m.0 && nearest.map_or(true, |(nearest_x, _)| x < nearest_x) {
+            nearest = Some((x, i));
+        }
+    }
+    let (x, edge) = match nearest {
+        Some(nearest) => nearest,
+        // 孔不在外轮廓内，退回到最近的顶点
+        None => {
+            return (0..n)
+                .min_by(|&a, &b| {
+                    let da = (polygon[a].0 - m.0).hypot(polygon[a].1 - m.1);
+                    let db = (polygon[b].0 - m.0).hypot(polygon[b].1 - m.1);
+                    da.total_cmp(&db)
+                })
+                .unwrap_or(0);
+        }
+    };
+    let intersection = (x, m.1);
+    let next = (edge + 1) % n;
+    let candidate = if polygon[edge].0 >= polygon[next].0 { edge } else { next };
+    let p = polygon[candidate];
+    if (p.0 - intersection.0).hypot(p.1 - intersection.1) < EPSILON {
+        return candidate;
+    }
+    // 三角形m、交点、p的方向
+    let orientation = cross(m, intersection, p).signum();
+    let in_triangle = |r: (f64, f64)| {
+        cross(m, intersection, r) * orientation >= -EPSILON
+            && cross(intersection, p, r) * orientation >= -EPSILON
+            && cross(p, m, r) * orientation >= -EPSILON
+    };
+    let ray_angle = |r: (f64, f64)| (r.1 - m.1).atan2(r.0 - m.0).abs();
+    let mut best = candidate;
+    for k in 0..n {
+        let r = polygon[k];
+        if k == candidate || r == p {
+            continue;
+        }
+        let prev = polygon[(k + n - 1) % n];
+        let after = polygon[(k + 1) % n];
+        // 逆时针多边形中向右转的顶点是凹顶点
+        if cross(prev, r, after) >= 0.0 || !in_triangle(r) {
+            continue;
+        }
+        let (angle, best_angle) = (ray_angle(r), ray_angle(polygon[best]));
+        let closer = (r.0 - m.0).hypot(r.1 - m.1) < (polygon[best].0 - m.0).hypot(polygon[best].1 - m.1);
+        if angle < best_angle - EPSILON || ((angle - best_angle).abs() <= EPSILON && closer) {
+            best = k;
+        }
+    }
+    best
+}
+
+// 把路径逐段输出为直线和圆弧
+fn add_outline(kicad_mod_data: &mut KicadModData, path: &EfooPath, layer: &str, width: f64) {
+    let (mut last_x, mut last_y) = (path.start_x, path.start_y);
+    for segment in &path.segments {
+        match *segment {
+            EfooSegment::Line { x, y } => {
+                kicad_mod_data.add_graphic_element_line(last_x, -last_y, x, -y, layer.to_string(), width);
+                (last_x, last_y) = (x, y);
+            }
+            EfooSegment::Arc { angle, x, y } => {
+                info!("圆弧: 起点({},{})，角度{}，结束点({},{})", last_x, last_y, angle, x, y);
+                kicad_mod_data.add_graphic_element_arc(last_x, last_y, angle, x, y, layer.to_string(), width);
+                (last_x, last_y) = (x, y);
+            }
+        }
+    }
+}
+
+// 焊盘不大于钻孔时没有铜环，即非金属化孔
+fn pad_without_copper(shape: &EfooPadShape, drill_x: f64, drill_y: f64) -> bool {
+    const EPSILON: f64 = 1e-6;
+    match shape {
+        EfooPadShape::Ellipse { width, height }
+        | EfooPadShape::Rect { width, height, .. }
+        | EfooPadShape::Oval { width, height } => *width <= drill_x + EPSILON && *height <= drill_y + EPSILON,
+        EfooPadShape::Poly(_) | EfooPadShape::Unknown(_) => false,
+    }
+}
+
+// 可以钻出的孔：圆、两端为半圆的矩形、由两段半圆弧和直线组成的长圆形
+// 返回中心坐标、角度、钻孔宽度(沿角度方向)和高度
+fn hole_shape(shape: &EfooShape) -> Option<(f64, f64, f64, f64, f64)> {
+    const EPSILON: f64 = 1e-3;
+    match shape {
+        EfooShape::Circle { center_x, center_y, radius } => Some((*center_x, *center_y, 0.0, 2.0 * radius, 2.0 * radius)),
+        EfooShape::Rect { x, y, width, height, rotation, radius } => {
+            if *radius < width.min(*height) / 2.0 - EPSILON {
+                return None;
+            }
+            let (sin, cos) = rotation.to_radians().sin_cos();
+            let (dx, dy) = (width / 2.0, -height / 2.0);
+            Some((x + dx * cos - dy * sin, y + dx * sin + dy * cos, normalize_angle(*rotation), *width, *height))
+        }
+        EfooShape::Path(path) => {
+            // 两段半圆弧的圆心和直径
+            let mut ends = Vec::new();
+            let (mut last_x, mut last_y) = (path.start_x, path.start_y);
+            for segment in &path.segments {
+                match *segment {
+                    EfooSegment::Line { x, y } => (last_x, last_y) = (x, y),
+                    EfooSegment::Arc { angle, x, y } => {
+                        if (angle.abs() - 180.0).abs() > EPSILON {
+                            return None;
+                        }
+                        ends.push(((last_x + x) / 2.0, (last_y + y) / 2.0, (x - last_x).hypot(y - last_y)));
+                        (last_x, last_y) = (x, y);
+                    }
+                }
+            }
+            let lines = path.segments.iter().filter(|s| matches!(s, EfooSegment::Line { .. })).count();
+            match ends.as_slice() {
+                [(x1, y1, d1), (x2, y2, d2)] if lines <= 2 && (d1 - d2).abs() < EPSILON => {
+                    let length = (x2 - x1).hypot(y2 - y1);
+                    let angle = if length > EPSILON { normalize_angle((y2 - y1).atan2(x2 - x1).to_degrees()) } else { 0.0 };
+                    Some(((x1 + x2) / 2.0, (y1 + y2) / 2.0, angle, length + d1, *d1))
+                }
+                _ => None,
+            }
+        }
+        EfooShape::Unsupported(_) => None,
+    }
+}
+
+// 把矩形转换为逆时针的路径，(x, y)为左上角，绕左上角旋转，圆角半径不超过短边的一半
+fn rect_path(x: f64, y: f64, width: f64, height: f64, rotation: f64, radius: f64) -> EfooPath {
+    let r = radius.clamp(0.0, width.min(height) / 2.0);
+    let (sin, cos) = rotation.to_radians().sin_cos();
+    // 以左上角为原点的坐标旋转后平移
+    let point = |px: f64, py: f64| (x + px * cos - py * sin, y + px * sin + py * cos);
+    let corners = [
+        ((r, 0.0), (0.0, -r)),
+        ((0.0, -height + r), (r, -height)),
+        ((width - r, -height), (width, -height + r)),
+        ((width, -r), (width - r, 0.0)),
+    ];
+    let (start_x, start_y) = point(width - r, 0.0);
+    let mut segments = Vec::new();
+    for (line_end, arc_end) in corners {
+        // 圆角半径为短边一半时，短边上的直线长度为0
+        let (lx, ly) = point(line_end.0, line_end.1);
+        let (last_x, last_y) = match segments.last() {
+            Some(EfooSegment::Line { x, y }) | Some(EfooSegment::Arc { x, y, .. }) => (*x, *y),
+            None => (start_x, start_y),
+        };
+        if (lx - last_x).hypot(ly - last_y) > 1e-9 {
+            segments.push(EfooSegment::Line { x: lx, y: ly });
+        }
+        if r > 0.0 {
+            let (ax, ay) = point(arc_end.0, arc_end.1);
+            segments.push(EfooSegment::Arc { angle: 90.0, x: ax, y: ay });
+        }
+    }
+    EfooPath { start_x, start_y, segments }
+}
+
+// 把圆转换为由两段半圆弧组成的路径
+fn circle_path(center_x: f64, center_y: f64, radius: f64) -> EfooPath {
+    EfooPath {
+        start_x: center_x + radius,
+        start_y: center_y,
+        segments: vec![
+            EfooSegment::Arc { angle: 180.0, x: center_x - radius, y: center_y },
+            EfooSegment::Arc { angle: 180.0, x: center_x + radius, y: center_y },
+        ],
+    }
+}
+
+// 把路径转换为点的坐标，格式为[x1, y1, x2, y2, ...]，圆弧按弦高误差chord_tolerance(mm)拟合为线段
+fn path_to_points(path: &EfooPath, chord_tolerance: f64) -> Vec<f64> {
+    // 路径坐标单位为mil
+    let tolerance = chord_tolerance / mil_to_mm(1.0);
+    let mut pts = vec![path.start_x, path.start_y];
+    // 与上一个顶点重合的点不重复输出
+    let push = |pts: &mut Vec<f64>, x: f64, y: f64| {
+        let (last_x, last_y) = (pts[pts.len() - 2], pts[pts.len() - 1]);
+        if (x - last_x).hypot(y - last_y) >= 1e-6 {
+            pts.push(x);
+            pts.push(y);
+        }
+    };
+    let (mut last_x, mut last_y) = (path.start_x, path.start_y);
+    for segment in &path.segments {
+        match *segment {
+            EfooSegment::Line { x, y } => {
+                push(&mut pts, x, y);
+                (last_x, last_y) = (x, y);
+            }
+            EfooSegment::Arc { angle, x, y } => {
+                let (start, end) = (Point::new(last_x, last_y), Point::new(x, y));
+                let num_segments = arc_segment_count(&start, &end, angle.to_radians(), tolerance);
+                let arc_pts = fit_arc_with_lines(start, end, angle.to_radians(), num_segments);
+                // 第一个点是上一段的终点，已经在pts里
+                for pt in arc_pts.iter().skip(1) {
+                    push(&mut pts, pt.x, pt.y);
+                }
+                (last_x, last_y) = (x, y);
+            }
+        }
+    }
+    pts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::Unconverted;
+
+    fn convert(lines: &[&str]) -> ConvertedFootprint {
+        parse_efoo_file(&lines.join("\n"), "FP", &[], &ConvertOptions::default())
+    }
+
+    // 双部件运放，标题为第一个部件的名称
+    fn lm358() -> ConvertedSymbol {
+        parse_esym_file(include_str!("../tests/fixtures/lm358.esym"), "LM358.1")
+    }
+
+    #[test]
+    fn unknown_type_is_unconverted() {
+        let footprint = convert(&[r#"["DOCTYPE","FOOTPRINT","1.1"]"#, r#"["HEAD",{}]"#, r#"["BOGUS","x"]"#]);
+        assert_eq!(
+            footprint.report.unconverted,
+            vec![Unconverted { line: 3, kind: "BOGUS".to_string(), reason: "暂不支持的类型".to_string() }]
+        );
+    }
+
+    fn square(x: f64, y: f64, size: f64) -> Vec<(f64, f64)> {
+        vec![(x, y), (x + size, y), (x + size, y + size), (x, y + size)]
+    }
+
+    fn pairs(pts: &[f64]) -> Vec<(f64, f64)> {
+        pts.chunks_exact(2).map(|p| (p[0], p[1])).collect()
+    }
+
+    // 两条边是否在端点以外的地方相交
+    fn edges_cross(a: (f64, f64), b: (f64, f64), c: (f64, f64), d: (f64, f64)) -> bool {
+        let cross = |o: (f64, f64), p: (f64, f64), q: (f64, f64)| (p.0 - o.0) * (q.1 - o.1) - (p.1 - o.1) * (q.0 - o.0);
+        let (d1, d2) = (cross(a, b, c), cross(a, b, d));
+        let (d3, d4) = (cross(c, d, a), cross(c, d, b));
+        d1 * d2 < -1e-9 && d3 * d4 < -1e-9
+    }
+
+    fn self_intersects(pts: &[(f64, f64)]) -> bool {
+        let n = pts.len();
+        (0..n).any(|i| (i + 1..n).any(|j| edges_cross(pts[i], pts[(i + 1) % n], pts[j], pts[(j + 1) % n])))
+    }
+
+    fn assert_keyhole(outer: &[(f64, f64)], holes: &[&[(f64, f64)]]) {
+        let merged = pairs(&keyhole_points(outer, holes));
+        assert!(!self_intersects(&merged), "多边形自相交: {:?}", merged);
+        // 切口往返的面积抵消，总面积为外轮廓减去孔
+        let expected = signed_area(outer).abs() - holes.iter().map(|h| signed_area(h).abs()).sum::<f64>();
+        assert!((signed_area(&merged) - expected).abs() < 1e-6, "面积{}，应为{}", signed_area(&merged), expected);
+    }
+
+    #[test]
+    fn keyhole_ring() {
+        let outer = shape_points(&EfooShape::Circle { center_x: 0.0, center_y: 0.0, radius: 100.0 }, 0.005).unwrap();
+        let inner = shape_points(&EfooShape::Circle { center_x: 0.0, center_y: 0.0, radius: 50.0 }, 0.005).unwrap();
+        assert_keyhole(&outer, &[&inner]);
+    }
+
+    #[test]
+    fn keyhole_concave_outline() {
+        // U形外轮廓，孔在左臂上，最近的顶点在右臂上，连过去会穿过缺口
+        let outer = vec![(0.0, 0.0), (100.0, 0.0), (100.0, 100.0), (55.0, 100.0), (55.0, 30.0), (45.0, 30.0), (45.0, 200.0), (0.0, 200.0)];
+        let hole = square(30.0, 90.0, 10.0);
+        assert_keyhole(&outer, &[&hole]);
+    }
+
+    #[test]
+    fn keyhole_several_holes() {
+        let outer = square(0.0, 0.0, 100.0);
+        let holes = [square(10.0, 40.0, 10.0), square(50.0, 45.0, 10.0), square(30.0, 10.0, 10.0), square(70.0, 70.0, 20.0)];
+        let holes: Vec<&[(f64, f64)]> = holes.iter().map(|h| h.as_slice()).collect();
+        assert_keyhole(&outer, &holes);
+    }
+
+    #[test]
+    fn keyhole_logo_with_holes_behind_each_other() {
+        // 梳形外轮廓，孔在同一高度上，向右的射线先碰到另一个孔
+        let outer = vec![(0.0, 0.0), (200.0, 0.0), (200.0, 100.0), (150.0, 100.0), (150.0, 60.0), (130.0, 60.0), (130.0, 100.0), (0.0, 100.0)];
+        let holes = [square(20.0, 20.0, 20.0), square(60.0, 20.0, 20.0), square(100.0, 25.0, 10.0), square(160.0, 20.0, 20.0)];
+        let holes: Vec<&[(f64, f64)]> = holes.iter().map(|h| h.as_slice()).collect();
+        assert_keyhole(&outer, &holes);
+    }
+
+    #[test]
+    fn contour_nesting() {
+        // 两个外轮廓各带一个孔，其中一个孔里还有一个岛
+        let contours = vec![
+            Some(square(0.0, 0.0, 100.0)),
+            Some(square(10.0, 10.0, 80.0)),
+            Some(square(40.0, 40.0, 20.0)),
+            Some(square(200.0, 0.0, 50.0)),
+            Some(square(210.0, 10.0, 10.0)),
+            None,
+        ];
+        let (is_hole, holes, orphans) = contour_holes(&contours);
+        assert_eq!(is_hole, vec![false, true, false, false, true, false]);
+        assert_eq!(holes, vec![vec![1], vec![], vec![], vec![4], vec![], vec![]]);
+        assert!(orphans.is_empty());
+    }
+
+    #[test]
+    fn orphan_hole_is_kept() {
+        // A的起点在B内但A不全在B内，C只在A内，找不到比C少一层的外轮廓
+        let contours = vec![Some(square(0.0, 0.0, 100.0)), Some(square(60.0, 60.0, 100.0)), Some(square(130.0, 130.0, 10.0))];
+        let (is_hole, holes, orphans) = contour_holes(&contours);
+        assert_eq!(is_hole, vec![false, true, false]);
+        assert_eq!(holes, vec![vec![1], vec![], vec![]]);
+        assert_eq!(orphans, vec![2]);
+    }
+
+    #[test]
+    fn fill_with_multiple_outlines() {
+        let footprint = convert(&[
+            r#"["FILL","e1",0,"",3,0,0,[["CIRCLE",0,0,100],["CIRCLE",0,0,50]]]"#,
+            r#"["FILL","e2",0,"",3,0,0,[[300,0,"L",400,0,400,100,300,100],[320,20,"L",340,20,340,40,320,40],[500,0,"L",600,0,600,100,500,100]]]"#,
+        ]);
+        assert!(footprint.report.unconverted.is_empty());
+        assert_eq!(footprint.content.matches("(fp_poly").count(), 3);
+    }
+
+    fn elibz(files: &[(&str, &[u8])]) -> Vec<u8> {
+        use std::io::Write;
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in files {
+            writer.start_file(*name, zip::write::SimpleFileOptions::default()).unwrap();
+            writer.write_all(content).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn fallback_titles_are_unique() {
+        let pad = br#"["PAD","e1",0,"",1,"1",0,0,0,null,["RECT",10,10,0],[],0,0,null,1,0,0,0,0,0]"#;
+        let bytes = elibz(&[("device.json", b"{}"), ("a.efoo", pad), ("b.efoo", pad)]);
+        let library = convert_elibz("LIB", &bytes, &ConvertOptions::default()).unwrap();
+        let titles: Vec<&str> = library.footprints.iter().map(|f| f.report.title.as_str()).collect();
+        assert_eq!(titles, vec!["LIB_a", "LIB_b"]);
+    }
+
+    #[test]
+    fn unreadable_file_does_not_abort_the_archive() {
+        let manifest = br#"{"footprints":{"a":{"title":"A"},"b":{"title":"B"}}}"#;
+        let bytes = elibz(&[("device.json", manifest), ("a.efoo", b"\xff\xfe"), ("b.efoo", b"")]);
+        let library = convert_elibz("LIB", &bytes, &ConvertOptions::default()).unwrap();
+        assert_eq!(library.footprints.len(), 1);
+        assert_eq!(library.footprints[0].report.title, "B");
+        assert_eq!(library.failed.len(), 1);
+        assert_eq!(library.failed[0].title, "A");
+        assert_eq!(library.failed[0].kind, ItemKind::Footprint);
+        assert_eq!(library.failed[0].status, crate::report::ItemStatus::Failed);
+    }
+
+    // fp_poly中所有的点，圆弧只取起点和终点
+    fn poly_vertices(content: &str) -> Vec<Vec<(String, String, String)>> {
+        content.lines().filter(|line| line.contains("(fp_poly")).map(|line| {
+            let mut vertices = Vec::new();
+            for item in line.split('(').skip(1) {
+                let words: Vec<&str> = item.trim_end_matches([')', ' ']).split_whitespace().collect();
+                if let [kind @ ("xy" | "start" | "end"), x, y] = words.as_slice() {
+                    vertices.push((kind.to_string(), x.to_string(), y.to_string()));
+                }
+            }
+            vertices
+        }).collect()
+    }
+
+    #[test]
+    fn polygon_arcs_need_kicad7() {
+        let lines = [r#"["FILL","e1",0,"",3,0,0,[[0,0,"L",100,0,100,0,"ARC",90,0,100,"L",0,0]]]"#];
+        let footprint = convert(&lines);
+        assert!(footprint.content.contains("(version 20211014)"));
+        assert!(!footprint.content.contains("(arc "));
+
+        let options = ConvertOptions { polygon_arcs: true, ..ConvertOptions::default() };
+        let footprint = parse_efoo_file(&lines.join("\n"), "FP", &[], &options);
+        assert!(footprint.content.contains("(version 20221018)"));
+        assert!(footprint.content.contains("(arc "));
+    }
+
+    #[test]
+    fn polygon_has_no_repeated_vertices() {
+        let lines = [r#"["FILL","e1",0,"",3,0,0,[[0,0,"L",100,0,100,0,"ARC",90,0,100,"ARC",-90,-100,200,"L",-100,0]]]"#];
+        let arcs = ConvertOptions { polygon_arcs: true, ..ConvertOptions::default() };
+        for options in [ConvertOptions::default(), arcs] {
+            let footprint = parse_efoo_file(&lines.join("\n"), "FP", &[], &options);
+            let polygons = poly_vertices(&footprint.content);
+            assert_eq!(polygons.len(), 1);
+            // 相邻圆弧的终点和起点本来就相同，其他相邻的点不能重复
+            for pair in polygons[0].windows(2) {
+                let (a, b) = (&pair[0], &pair[1]);
+                assert!((a.0 == "end" && b.0 == "start") || (&a.1, &a.2) != (&b.1, &b.2), "{}", footprint.content);
+            }
+        }
+    }
+
+    #[test]
+    fn esym_pins_and_attributes() {
+        let content = lm358().symbol.generate_content();
+        // 符号属性，位号去掉问号并放在Designator的位置
+        assert!(content.contains("(property \"Reference\" \"U\" (id 0) (at 0.0000 10.1600 0)\n      (effects (font (size 1.27 1.27))))"));
+        assert!(content.contains("(property \"ki_description\" \"Dual \\\"op-amp\\\"\" (id 6) (at 0.0000 0.0000 0)\n      (effects (font (size 1.27 1.27)) hide))"));
+        assert!(content.contains("(property \"MPN\" \"LM358DR\" (id 4)"));
+        assert!(content.contains("(property \"Supplier Part\" \"C7950\" (id 5)"));
+        // 引脚的名称和编号来自引脚的ATTR行
+        assert!(content.contains(concat!(
+            "      (pin input line (at -10.1600 2.5400 0) (length 5.0800)\n",
+            "        (name \"+\" (effects (font (size 1.27 1.27))))\n",
+            "        (number \"3\" (effects (font (size 1.27 1.27)))))\n",
+        )));
+        assert!(content.contains("(pin power_in line (at 0.0000 12.7000 270) (length 5.0800) hide\n        (name \"V+\""));
+        // 图形使用线型的线宽和填充
+        assert!(content.contains(concat!(
+            "      (polyline\n",
+            "        (pts (xy -5.0800 7.6200) (xy -5.0800 -7.6200) (xy 7.6200 0.0000) (xy -5.0800 7.6200))\n",
+            "        (stroke (width 0.5080) (type default) (color 0 0 0 0))\n",
+            "        (fill (type background)))\n",
+        )));
+        assert!(content.contains("      (text \"A\\\"2\" (at 0.0000 0.0000 0)\n        (effects (font (size 2.5400 2.5400))))\n"));
+    }
+
+    #[test]
+    fn esym_pin_types_and_shapes() {
+        let converted = lm358();
+        let content = converted.symbol.generate_content();
+        assert!(content.contains("(pin input line (at -10.1600 2.5400 0)"));
+        assert!(content.contains("(pin input inverted (at -10.1600 -2.5400 0)"));
+        // 引脚属性里的类型优先于PIN行里的
+        assert!(content.contains("(pin output line (at 12.7000 0.0000 180)"));
+        assert!(content.contains("(pin power_in line (at 0.0000 12.7000 270)"));
+        // 未知的类型和形状使用默认值，并记录为未转换
+        assert!(content.contains("(pin unspecified clock (at 0.0000 -12.7000 90)"));
+        assert_eq!(
+            converted.report.unconverted,
+            vec![
+                Unconverted { line: 24, kind: "PIN".to_string(), reason: "未知的引脚电气类型: WEIRD".to_string() },
+                Unconverted { line: 27, kind: "PIN".to_string(), reason: "未知的引脚形状: SQUIGGLE".to_string() },
+            ]
+        );
+    }
+
+    #[test]
+    fn esym_parts_are_units() {
+        let converted = lm358();
+        // 标题是第一个部件的名称时去掉部件序号
+        assert_eq!(converted.report.title, "LM358");
+        let content = converted.symbol.generate_content();
+        assert!(content.contains("  (symbol \"LM358\" "));
+        assert!(content.contains("(property \"Value\" \"LM358\" (id 1)"));
+        // 每个部件是一个单元，图形和引脚属于所在的部件
+        let unit_1 = content.find("    (symbol \"LM358_1_1\"\n").unwrap();
+        let unit_2 = content.find("    (symbol \"LM358_2_1\"\n").unwrap();
+        assert!(unit_1 < unit_2);
+        assert!(!content.contains("LM358_0_1"));
+        let (first, second) = content[unit_1..].split_at(unit_2 - unit_1);
+        assert!(first.contains("(polyline"));
+        assert!(first.contains("(number \"1\""));
+        assert!(!first.contains("(rectangle"));
+        assert!(second.contains("(rectangle"));
+        assert!(second.contains("(number \"4\""));
+        assert!(second.contains("(number \"8\""));
+        assert!(!second.contains("(number \"1\""));
+    }
+
+    #[test]
+    fn multi_part_symbol_title() {
+        let parts = vec!["LM358.1".to_string(), "LM358.2".to_string()];
+        assert_eq!(symbol_title("LM358.1", &parts), "LM358");
+        assert_eq!(symbol_title("LM358", &parts), "LM358");
+        // 不是部件名称的标题和单部件符号保持不变
+        assert_eq!(symbol_title("Conn_2.54", &parts), "Conn_2.54");
+        assert_eq!(symbol_title("LM358.1", &parts[..1]), "LM358.1");
+    }
+
+    #[test]
+    fn smd_pad_names_are_quoted() {
+        let footprint = convert(&[
+            r#"["PAD","e1",0,"",1,"",0,0,0,null,["RECT",10,10,0],[],0,0,0,1,0,0,0,0,0]"#,
+            r#"["PAD","e2",0,"",1,"A B",100,0,0,null,["ELLIPSE",10,10],[],0,0,0,1,0,0,0,0,0]"#,
+            r#"["PAD","e3",0,"",1,"C\"D",200,0,0,null,["ELLIPSE",10,20],[],0,0,0,1,0,0,0,0,0]"#,
+            r#"["PAD","e4",0,"",1,"E F",300,0,0,null,["POLY",[290,0,"L",310,0,300,10]],[],0,0,0,1,0,0,0,0,0]"#,
+        ]);
+        assert!(footprint.report.unconverted.is_empty());
+        assert!(footprint.content.contains("(pad \"\" smd rect "));
+        assert!(footprint.content.contains("(pad \"A B\" smd circle "));
+        assert!(footprint.content.contains("(pad \"C\\\"D\" smd oval "));
+        assert!(footprint.content.contains("(pad \"E F\" smd custom "));
+    }
+
+    #[test]
+    fn thru_hole_pad_names_are_quoted() {
+        let footprint = convert(&[
+            r#"["PAD","e1",0,"",12,"",0,0,0,["ROUND",20,20],["ELLIPSE",60,60],[],0,0,0,1,0,0,0,0,0]"#,
+            r#"["PAD","e2",0,"",12,"A B",200,0,0,["ROUND",20,20],["OVAL",60,100],[],0,0,0,1,0,0,0,0,0]"#,
+            r#"["PAD","e3",0,"",12,"C\"D",400,0,0,["ROUND",20,20],["RECT",60,60],[],0,0,0,1,0,0,0,0,0]"#,
+        ]);
+        assert!(footprint.report.unconverted.is_empty());
+        assert!(footprint.content.contains("(pad \"\" thru_hole circle "));
+        assert!(footprint.content.contains("(pad \"A B\" thru_hole oval "));
+        assert!(footprint.content.contains("(pad \"C\\\"D\" thru_hole rect "));
+    }
+
+    #[test]
+    fn unplated_pad_is_np_hole() {
+        let footprint = convert(&[
+            r#"["PAD","e1",0,"",12,"1",0,0,0,["ROUND",20,20],["ELLIPSE",60,60],[],0,0,0,0,0,0,0,0,0]"#,
+            r#"["PAD","e2",0,"",12,"2",200,0,0,["ROUND",60,60],["ELLIPSE",60,60],[],0,0,0,1,0,0,0,0,0]"#,
+            r#"["PAD","e3",0,"",12,"3",400,0,0,["ROUND",20,20],["ELLIPSE",60,60],[],0,0,0,1,0,0,0,0,0]"#,
+        ]);
+        // 非金属化的焊盘和不大于钻孔的焊盘都输出为非金属化孔，孔外的铜皮给出警告
+        assert!(footprint.content.contains("(pad \"1\" np_thru_hole circle (at 0.000000 -0.000000) (size 0.508000 0.508000) (drill 0.508000)"));
+        assert!(footprint.content.contains("(pad \"2\" np_thru_hole circle (at 5.080000 -0.000000) (size 1.524000 1.524000) (drill 1.524000)"));
+        assert!(footprint.content.contains("(pad \"3\" thru_hole circle "));
+        assert_eq!(footprint.report.warnings.len(), 1);
+        assert!(footprint.content.contains("(attr through_hole)"));
+    }
+
+    #[test]
+    fn hole_offset_is_drill_offset() {
+        let footprint = convert(&[
+            r#"["PAD","e1",0,"",12,"1",0,0,0,["ROUND",20,20],["RECT",60,60],[],10,5,0,1,0,0,0,0,0]"#,
+            r#"["PAD","e2",0,"",12,"2",200,0,0,["SLOT",20,40],["OVAL",60,100],[],0,-10,0,1,0,0,0,0,0]"#,
+            r#"["PAD","e3",0,"",12,"3",400,0,90,["ROUND",20,20],["ELLIPSE",20,20],[],10,0,0,0,0,0,0,0,0]"#,
+        ]);
+        assert!(footprint.report.unconverted.is_empty());
+        // 偏移在焊盘坐标系中，Y轴取反
+        assert!(footprint.content.contains("(drill 0.508000 (offset 0.254000 -0.127000))"));
+        assert!(footprint.content.contains("(drill oval 0.508000 1.016000 (offset 0.000000 0.254000))"));
+        // 非金属化孔直接移到偏移后的位置
+        assert!(footprint.content.contains("(pad \"3\" np_thru_hole circle (at 10.160000 -0.254000) (size 0.508000 0.508000) (drill 0.508000)"));
+    }
+
+    #[test]
+    fn multi_layer_shapes_are_holes() {
+        let footprint = convert(&[
+            r#"["FILL","e1",0,"",12,0,0,[["CIRCLE",0,0,10]]]"#,
+            r#"["FILL","e2",0,"",12,0,0,[["R",100,0,100,40,0,20]]]"#,
+            r#"["FILL","e3",0,"",12,0,0,[[300,0,"L",400,0,350,50,300,0]]]"#,
+        ]);
+        assert!(footprint.report.unconverted.is_empty());
+        assert!(footprint.content.contains("(pad \"\" np_thru_hole circle (at 0.000000 -0.000000) (size 0.508000 0.508000) (drill 0.508000)"));
+        assert!(footprint.content.contains("(pad \"\" np_thru_hole oval (at 3.810000 0.508000 0) (size 2.540000 1.016000) (drill oval 2.540000 1.016000)"));
+        // 三角形无法钻孔，输出为Edge.Cuts上的轮廓
+        assert_eq!(footprint.content.matches("(layer \"Edge.Cuts\")").count(), 3);
+        assert_eq!(footprint.report.warnings.len(), 1);
+    }
+
+    #[test]
+    fn roundrect_ratio_from_percentage() {
+        assert_eq!(roundrect_rratio(0.0), 0.0);
+        assert_eq!(roundrect_rratio(25.0), 0.25);
+        assert_eq!(roundrect_rratio(50.0), 0.5);
+        assert_eq!(roundrect_rratio(80.0), 0.5);
+    }
+
+    #[test]
+    fn thru_hole_rect_keeps_corner_radius() {
+        let footprint = convert(&[
+            r#"["PAD","e1",0,"",12,"1",0,0,90,["ROUND",20,20],["RECT",60,80,25],[],0,0,0,1,0,0,0,0,0]"#,
+            r#"["PAD","e2",0,"",12,"2",200,0,0,["ROUND",20,20],["RECT",60,80,0],[],0,0,0,1,0,0,0,0,0]"#,
+        ]);
+        assert!(footprint.content.contains("(pad \"1\" thru_hole roundrect (at 0.000000 -0.000000 90) (size 1.524000 2.032000) (drill 0.508000) (layers *.Cu *.Mask) (roundrect_rratio 0.250000)"));
+        assert!(footprint.content.contains("(pad \"2\" thru_hole rect "));
+    }
+
+    #[test]
+    fn chamfered_pad_keeps_rotation() {
+        // 右上、左下两个角切角的80x40矩形，多边形为旋转90度后的绝对坐标
+        let footprint = convert(&[
+            r#"["PAD","e1",0,"",1,"1",0,0,90,null,["POLY",[20,-30,"L",20,40,-10,40,-20,30,-20,-40,10,-40,20,-30]],[],0,0,0,1,0,0,0,0,0]"#,
+        ]);
+        assert!(footprint.report.unconverted.is_empty());
+        assert!(footprint.content.contains("(pad \"1\" smd roundrect (at 0.000000 -0.000000 90) (size 2.032000 1.016000)"));
+        assert!(footprint.content.contains("(chamfer_ratio 0.250000) (chamfer top_right bottom_left)"));
+    }
+
+    #[test]
+    fn via_is_not_tied_to_failed_pad() {
+        let footprint = convert(&[
+            r#"["PAD","e1",0,"",12,"1",0,0,0,["TRIANGLE",20],["ELLIPSE",60,60],[],0,0,0,1,0,2,2,0,0]"#,
+            r#"["VIA","e2",0,"",0,0,0,12,24]"#,
+        ]);
+        assert_eq!(footprint.report.unconverted.len(), 1);
+        assert!(footprint.content.contains("(pad \"\" thru_hole circle"));
+        assert!(!footprint.content.contains("(pad \"1\""));
+    }
+
+    #[test]
+    fn via_is_not_tied_to_pad_on_unknown_layer() {
+        let footprint = convert(&[
+            r#"["PAD","e1",0,"",99,"2",0,0,0,null,["RECT",60,60,0],[],0,0,0,1,0,2,2,0,0]"#,
+            r#"["VIA","e2",0,"",0,0,0,12,24]"#,
+        ]);
+        assert_eq!(footprint.report.unconverted.len(), 1);
+        assert!(footprint.content.contains("(pad \"\" thru_hole circle"));
+        assert!(!footprint.content.contains("(pad \"2\""));
+    }
+
+    #[test]
+    fn via_is_not_tied_to_pad_with_unknown_shape() {
+        let footprint = convert(&[
+            r#"["PAD","e1",0,"",1,"3",0,0,0,null,["STAR",60,60],[],0,0,0,1,0,2,2,0,0]"#,
+            r#"["PAD","e2",0,"",12,"4",0,0,0,["ROUND",20,20],["STAR",60,60],[],0,0,0,1,0,2,2,0,0]"#,
+            r#"["VIA","e3",0,"",0,0,0,12,24]"#,
+        ]);
+        assert_eq!(footprint.report.unconverted.len(), 2);
+        assert!(footprint.content.contains("(pad \"\" thru_hole circle"));
+        assert!(!footprint.content.contains("(pad \"3\"") && !footprint.content.contains("(pad \"4\""));
+    }
+
+    #[test]
+    fn malformed_line_is_unconverted() {
+        let footprint = convert(&[r#"["PAD","bad"]"#]);
+        assert_eq!(
+            footprint.report.unconverted,
+            vec![Unconverted { line: 1, kind: "PAD".to_string(), reason: "焊盘数据长度不足".to_string() }]
+        );
+    }
+
+    #[test]
+    fn slot_holes_are_oval_drills() {
+        let footprint = convert(&[
+            r#"["PAD","e1",0,"",12,"1",0,0,0,["SLOT",20,40],["ELLIPSE",60,60],[],0,0,0,1,0,0,0,0,0]"#,
+            r#"["PAD","e2",0,"",12,"2",200,0,0,["SLOT",20,40],["RECT",60,100,0],[],0,0,90,1,0,0,0,0,0]"#,
+            r#"["PAD","e3",0,"",12,"3",400,0,0,["SLOT",20,40],["OVAL",60,100],[],0,0,30,1,0,0,0,0,0]"#,
+        ]);
+        assert!(footprint.report.unconverted.is_empty());
+        assert!(footprint.content.contains("(pad \"1\" thru_hole circle (at 0.000000 -0.000000 0) (size 1.524000 1.524000) (drill oval 0.508000 1.016000) (layers *.Cu *.Mask)"));
+        // 钻孔相对焊盘旋转90度时交换宽高
+        assert!(footprint.content.contains("(pad \"2\" thru_hole rect (at 5.080000 -0.000000 0) (size 1.524000 2.540000) (drill oval 1.016000 0.508000) (layers *.Cu *.Mask)"));
+        // 不是90度的倍数时取最近的倍数并警告
+        assert!(footprint.content.contains("(pad \"3\" thru_hole oval (at 10.160000 -0.000000 0) (size 1.524000 2.540000) (drill oval 0.508000 1.016000) (layers *.Cu *.Mask)"));
+        assert_eq!(footprint.report.warnings, vec!["第3行PAD: 槽孔相对焊盘旋转30度，KiCad只支持90度的倍数，已取0度".to_string()]);
+    }
+
+    #[test]
+    fn thru_hole_poly_pad_is_custom() {
+        let footprint = convert(&[
+            r#"["PAD","e1",0,"",12,"1",100,0,90,["SLOT",20,40],["POLY",[100,40,"L",60,-20,140,-20,100,40]],[],0,0,0,1,0,0,0,0,0]"#,
+        ]);
+        assert!(footprint.report.unconverted.is_empty());
+        // 锚点焊盘与钻孔的较小边一样大，铜皮形状转到焊盘自身的坐标系
+        assert!(footprint.content.contains(concat!(
+            "  (pad \"1\" thru_hole custom (at 2.540000 -0.000000 90) (size 0.508000 0.508000) (drill oval 0.508000 1.016000) (layers *.Cu *.Mask)\n",
+            "    (options (clearance outline) (anchor circle))\n",
+            "    (primitives\n",
+            "      (gr_poly (pts (xy 1.016000 -0.000000) (xy -0.508000 -1.016000) (xy -0.508000 1.016000) (xy 1.016000 -0.000000)\n",
+            "      ) (width 0) (fill yes))\n",
+            "  ) (solder_mask_margin 0.051) (tstamp ",
+        )));
+        assert!(footprint.content.contains("(attr through_hole)"));
+    }
+
+    #[test]
+    fn footprint_attr_from_pads() {
+        let smd = r#"["PAD","e1",0,"",1,"1",0,0,0,null,["RECT",10,10,0],[],0,0,0,1,0,0,0,0,0]"#;
+        let thru_hole = r#"["PAD","e2",0,"",12,"2",100,0,0,["ROUND",20,20],["ELLIPSE",60,60],[],0,0,0,1,0,0,0,0,0]"#;
+        let via = r#"["VIA","e3",0,"",0,0,0,12,24]"#;
+        // 过孔不影响元件类型
+        assert!(convert(&[smd, via]).content.contains("\n(attr smd)\n"));
+        assert!(convert(&[smd, thru_hole]).content.contains("\n(attr through_hole)\n"));
+        assert!(!convert(&[r#"["POLY","e4",0,"",3,10,[0,0,"L",100,0]]"#]).content.contains("(attr"));
+        // 器件属性在前，封装自身的属性优先
+        let device_attrs = vec![
+            ("Add into BOM".to_string(), "no".to_string()),
+            ("Exclude From Position Files".to_string(), "yes".to_string()),
+        ];
+        let lines = [smd, r#"["ATTR","e5",0,"",3,null,null,"Exclude From Position Files","no",false,false]"#].join("\n");
+        let footprint = parse_efoo_file(&lines, "FP", &device_attrs, &ConvertOptions::default());
+        assert!(footprint.content.contains("\n(attr smd exclude_from_bom)\n"));
+    }
+
+    #[test]
+    fn strings_are_fp_text() {
+        let footprint = convert(&[
+            r#"["STRING","e1",0,4,100,50,"A\"B","default",40,0,0,0,9,450,0,0,true]"#,
+            r#"["STRING","e2",0,3,0,0,"C","Arial",50,5,0,0,5,-90,0,0,false]"#,
+            r#"["STRING","e3",0,12,0,0,"D","default",50,5,0,0,5,0,0,0,false]"#,
+        ]);
+        // 角度规范到[0, 360)，底层文本镜像
+        assert!(footprint.content.contains("  (fp_text user \"A\\\"B\" (at 2.540000 -1.270000 90) (layer \"B.SilkS\")\n    (effects (font (size 1.016000 1.016000) (thickness 0.152400)) (justify right bottom mirror))\n"));
+        assert!(footprint.content.contains("  (fp_text user \"C\" (at 0.000000 -0.000000 270) (layer \"F.SilkS\")\n    (effects (font (size 1.270000 1.270000) (thickness 0.127000)))\n"));
+        assert!(!footprint.content.contains("\"D\""));
+        assert_eq!(footprint.report.warnings, vec!["第2行文本的字体Arial替换为KiCad默认字体".to_string()]);
+        assert_eq!(
+            footprint.report.unconverted,
+            vec![Unconverted { line: 3, kind: "STRING".to_string(), reason: "文本不能放在该层: 12".to_string() }]
+        );
+    }
+
+    #[test]
+    fn fields_follow_designator_and_name() {
+        let footprint = convert(&[
+            r#"["PAD","e1",0,"",1,"1",0,0,0,null,["RECT",10,10,0],[],0,0,0,1,0,0,0,0,0]"#,
+            r#"["ATTR","e2",0,"",4,100,200,"Designator","U1",false,false,"default",40,6,0,0,5,450]"#,
+            r#"["ATTR","e3",0,"",4,0,-100,"Name","X",false,true,"default",0,0,0,0,5,180]"#,
+        ]);
+        // 底层的位号放在B.SilkS并镜像，隐藏的位号输出为隐藏的参考标识
+        assert!(footprint.content.contains("  (fp_text reference \"REF**\" (at 2.540000 -5.080000 90) (layer \"B.SilkS\") hide\n    (effects (font (size 1.016000 1.016000) (thickness 0.152400)) (justify mirror))\n"));
+        assert!(footprint.content.contains("  (fp_text value \"FP\" (at 0.000000 2.540000 180) (layer \"B.Fab\")\n    (effects (font (size 1.000000 1.000000) (thickness 0.150000)) (justify mirror))\n"));
+        assert!(footprint.report.warnings.is_empty());
+    }
+
+    #[test]
+    fn fields_fall_back_to_defaults() {
+        let footprint = convert(&[
+            r#"["PAD","e1",0,"",1,"1",0,0,0,null,["RECT",100,100,0],[],0,0,0,1,0,0,0,0,0]"#,
+            r#"["ATTR","e2",0,"",1,0,0,"Designator","U1",false,true,"default",40,6,0,0,5,0]"#,
+        ]);
+        // 位号不在丝印层或装配层时放在F.SilkS并警告
+        assert!(footprint.content.contains("  (fp_text reference \"REF**\" (at 0.000000 -0.000000 0) (layer \"F.SilkS\")\n    (effects (font (size 1.016000 1.016000) (thickness 0.152400)))\n"));
+        assert_eq!(footprint.report.warnings, vec!["位号不能放在层1，参考标识放在F.SilkS".to_string()]);
+        // 没有名称时值放在外框下方一个字高处
+        assert!(footprint.content.contains("  (fp_text value \"FP\" (at 0.000000 2.270000 0) (layer \"F.Fab\")\n"));
+    }
+
+    #[test]
+    fn regions_are_keepout_zones() {
+        let footprint = convert(&[
+            r#"["REGION","e1",0,1,0,[["R",0,0,100,50,0,0]],[3,5,9]]"#,
+            r#"["REGION","e2",0,12,0,[["CIRCLE",0,0,10]],[6,7,2]]"#,
+            r#"["REGION","e3",0,3,0,[["CIRCLE",0,0,10]],[3]]"#,
+            r#"["REGION","e4",0,1,0,[["CIRCLE",0,0,10]],[9]]"#,
+        ]);
+        assert!(footprint.content.contains("  (zone (net 0) (net_name \"\") (layers \"F.Cu\") (tstamp "));
+        assert!(footprint.content.contains(concat!(
+            "    (keepout (tracks not_allowed) (vias not_allowed) (pads allowed) (copperpour allowed) (footprints allowed))\n",
+            "    (fill (thermal_gap 0.508) (thermal_bridge_width 0.508))\n",
+            "    (polygon\n",
+            "      (pts (xy 2.540000 -0.000000) (xy 0.000000 -0.000000) (xy 0.000000 1.270000) (xy 2.540000 1.270000) (xy 2.540000 -0.000000)\n",
+        )));
+        // 多层的区域放在所有铜层，重复的禁止类型只输出一次
+        assert!(footprint.content.contains("(layers \"*.Cu\")"));
+        assert!(footprint.content.contains("(keepout (tracks allowed) (vias allowed) (pads allowed) (copperpour not_allowed) (footprints not_allowed))"));
+        assert_eq!(footprint.content.matches("(zone ").count(), 2);
+        assert_eq!(
+            footprint.report.unconverted,
+            vec![
+                Unconverted { line: 3, kind: "REGION".to_string(), reason: "禁止区域不能放在该层: 3".to_string() },
+                Unconverted { line: 4, kind: "REGION".to_string(), reason: "没有可转换的禁止类型".to_string() },
+            ]
+        );
+        assert_eq!(
+            footprint.report.warnings,
+            vec!["第1行区域的禁止类型9无法转换".to_string(), "第4行区域的禁止类型9无法转换".to_string()]
+        );
+    }
+
+    #[test]
+    fn rect_shapes_are_fp_rect_or_outlines() {
+        let footprint = convert(&[
+            r#"["POLY","e1",0,"",3,10,[["R",0,0,100,50,0,0]]]"#,
+            r#"["FILL","e2",0,"",9,0,0,[["R",200,0,100,50,0,0]]]"#,
+            r#"["POLY","e3",0,"",3,10,[["R",0,100,100,50,0,10]]]"#,
+        ]);
+        assert!(footprint.report.unconverted.is_empty());
+        // 不旋转、没有圆角的矩形输出为fp_rect，矩形的起点为左上角
+        assert!(footprint.content.contains("  (fp_rect (start 0.000000 -0.000000) (end 2.540000 1.270000) (layer \"F.SilkS\") (width 0.254000) (fill none) (tstamp "));
+        assert!(footprint.content.contains("  (fp_rect (start 5.080000 -0.000000) (end 7.620000 1.270000) (layer \"F.Fab\") (width 0.000000) (fill solid) (tstamp "));
+        // 圆角矩形的轮廓为四条直线和四段圆弧
+        assert_eq!(footprint.content.matches("(fp_rect ").count(), 2);
+        assert_eq!(footprint.content.matches("(fp_line ").count(), 4);
+        assert_eq!(footprint.content.matches("(fp_arc ").count(), 4);
+        assert!(footprint.content.contains("  (fp_line (start 2.286000 -2.540000) (end 0.254000 -2.540000) (layer \"F.SilkS\") (width 0.254000) "));
+        assert!(footprint.content.contains("  (fp_arc (start 0.000000 -2.286000) (mid 0.074395 -2.465605) (end 0.254000 -2.540000) (layer F.SilkS) (width 0.254000)"));
+        assert!(footprint.content.contains("  (fp_line (start 0.000000 -2.286000) (end 0.000000 -1.524000) (layer \"F.SilkS\") (width 0.254000) "));
+    }
+}
@@ -0,0 +1,1751 @@
+//! kicad_tool - 用于处理KiCad文件格式的工具模块
+//!
+//! 该模块提供创建kicad_mod文件和修改kicad_sym文件的功能框架
+
+use std::collections::BTreeMap;
+use chrono::{DateTime, Local};
+use uuid::Uuid;
+use log::{info}; // 添加日志库引用
+
+/// 轮廓中的一段，坐标为EasyEDA坐标(Y轴向上)，单位mil
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutlineSegment {
+    /// 直线，到终点
+    Line { x: f64, y: f64 },
+    /// 圆弧，从上一段的终点到终点，角度单位为度，逆时针为正
+    Arc { angle: f64, x: f64, y: f64 },
+}
+
+/// 焊盘的位置、角度和大小，坐标为EasyEDA坐标(Y轴向上)，单位mil
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PadGeometry {
+    /// 中心的X坐标
+    pub center_x: f64,
+    /// 中心的Y坐标
+    pub center_y: f64,
+    /// 角度
+    pub angle: f64,
+    /// 宽度，圆形焊盘为直径
+    pub width: f64,
+    /// 高度
+    pub height: f64,
+}
+
+impl PadGeometry {
+    pub fn new(center_x: f64, center_y: f64, angle: f64, width: f64, height: f64) -> Self {
+        PadGeometry { center_x, center_y, angle, width, height }
+    }
+}
+
+/// 通孔焊盘的钻孔，单位mil
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PadDrill {
+    /// 钻孔宽度
+    pub width: f64,
+    /// 钻孔高度，与宽度相同时为圆孔，否则为槽孔
+    pub height: f64,
+    /// 钻孔中心相对焊盘中心的X偏移，焊盘自身的坐标系
+    pub offset_x: f64,
+    /// 钻孔中心相对焊盘中心的Y偏移，焊盘自身的坐标系，Y轴向上
+    pub offset_y: f64,
+}
+
+impl PadDrill {
+    pub fn new(width: f64, height: f64) -> Self {
+        PadDrill { width, height, offset_x: 0.0, offset_y: 0.0 }
+    }
+
+    // 生成(drill ...)，偏移为0时不输出offset
+    fn generate(&self) -> String {
+        let size = if self.width == self.height {
+            format!("{:.6}", mil_to_mm(self.width))
+        } else {
+            format!("oval {:.6} {:.6}", mil_to_mm(self.width), mil_to_mm(self.height))
+        };
+        if self.offset_x == 0.0 && self.offset_y == 0.0 {
+            format!("(drill {})", size)
+        } else {
+            format!("(drill {} (offset {:.6} {:.6}))", size, mil_to_mm(self.offset_x), - mil_to_mm(self.offset_y))
+        }
+    }
+}
+
+/// 贴片焊盘的阻焊和锡膏外扩距离，单位mil
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PadMargins {
+    /// 阻焊距离
+    pub solder_mask: f64,
+    /// 锡膏距离
+    pub solder_paste: f64,
+}
+
+/// 用于暂存kicad_mod文件数据的结构体
+#[derive(Debug, Clone)]
+pub struct KicadModData {
+    /// 文件头信息
+    header: String,
+    /// 版本
+    version: String,
+    /// 生成者
+    generator: String,
+    /// 所在层
+    layer: String,
+    /// 最后编辑时间
+    tedit: String,
+    /// 描述
+    description: String,
+    /// 标签
+    tags: String,
+    /// 是否有贴片焊盘，用于确定元件类型
+    has_smd_pad: bool,
+    /// 是否有通孔焊盘，用于确定元件类型
+    has_thru_hole_pad: bool,
+    /// 不出现在坐标文件中
+    exclude_from_pos_files: bool,
+    /// 不出现在BOM中
+    exclude_from_bom: bool,
+    /// 值
+    value: String,
+    /// 参考标识的位置和大小
+    reference_field: FieldText,
+    /// 值的位置和大小
+    value_field: FieldText,
+    /// 文本元素
+    text_elements: Vec<String>,
+    /// 图形元素
+    graphic_elements: Vec<String>,
+    /// 焊盘信息
+    pads: Vec<String>,
+    /// 禁止区域
+    zones: Vec<String>,
+    // 3D模型数据
+    // other_data: String,
+}
+
+// 封装的参考标识、值文本，坐标和大小的单位为mil，Y轴向上
+#[derive(Debug, Clone)]
+struct FieldText {
+    x: f64,
+    y: f64,
+    angle: f64,
+    height: f64,
+    thickness: f64,
+    layer: String,
+    hide: bool,
+}
+
+impl FieldText {
+    // 默认字高1mm，笔画宽度0.15mm
+    fn new(x: f64, y: f64, layer: &str) -> Self {
+        FieldText { x, y, angle: 0.0, height: 1.0 / 0.0254, thickness: 0.15 / 0.0254, layer: layer.to_string(), hide: false }
+    }
+
+    fn generate(&self, kind: &str, text: &str) -> String {
+        // 底层的文本镜像显示
+        let justify = if self.layer.starts_with("B.") { " (justify mirror)" } else { "" };
+        format!("  (fp_text {} \"{}\" (at {:.6} {:.6} {}) (layer \"{}\"){}\n    (effects (font (size {:.6} {:.6}) (thickness {:.6})){})\n    (tstamp {}))",
+            kind, escape_string(text), mil_to_mm(self.x), - mil_to_mm(self.y), self.angle, self.layer, if self.hide { " hide" } else { "" },
+            mil_to_mm(self.height), mil_to_mm(self.height), mil_to_mm(self.thickness), justify, Uuid::new_v4())
+    }
+
+    fn set_position(&mut self, x: f64, y: f64, angle: f64, height: f64, thickness: f64) {
+        self.x = x;
+        self.y = y;
+        self.angle = angle;
+        self.height = height;
+        self.thickness = thickness;
+    }
+}
+
+
+impl KicadModData {
+    /// 创建一个新的KicadModData实例
+    ///
+    /// # 参数
+    ///
+    /// * `module_name` - 模块名称
+    ///
+    /// # 返回值
+    ///
+    /// * `KicadModData` - 新创建的实例
+    pub fn new(module_name: &str) -> Self {
+
+        let now: DateTime<Local> = Local::now();
+        // 获取 YYYYMMDD 格式
+        //let date_str = now.format("%Y%m%d").to_string();
+        // 获取 16 位十六进制时间戳（秒）
+        let hex_tedit = format!("{:016x}", now.timestamp() as u64);
+        // 生成UUIDv4，应该唯一，防止冲突
+        // Uuid::new_v4()
+        // 文件头
+        let header = format!("(footprint \"{}\" ", 
+                            module_name);
+
+        info!("创建新的KicadModData实例: {}", module_name); // 添加日志
+
+        KicadModData {
+            header,
+            version: format!("(version {})", "20211014"),
+            generator: format!("(generator {})", "pcbnew"),
+            layer: format!("(layer \"{}\")", "F.Cu"),
+            tedit: format!("(tedit {})", hex_tedit),
+            description: format!("(descr \"{}\")", ""),  
+            tags: format!("(tags \"{}\")", ""),
+            has_smd_pad: false,
+            has_thru_hole_pad: false,
+            exclude_from_pos_files: false,
+            exclude_from_bom: false,
+            value: module_name.to_string(),
+            // 未设置位置时参考标识在上方5mm，值在下方5mm
+            reference_field: FieldText::new(0.0, 5.0 / 0.0254, "F.SilkS"),
+            value_field: FieldText::new(0.0, -5.0 / 0.0254, "F.Fab"),
+            text_elements: Vec::new(),
+            graphic_elements: Vec::new(),
+            pads: Vec::new(),
+            zones: Vec::new(),
+            // other_data: String::new(),
+        }
+    }
+
+    
+    /// 添加图形元素:直线
+    ///
+    /// # 参数
+    ///
+    /// * `element` - 图形元素字符串
+    pub fn add_graphic_element_line(&mut self, start_x: f64, start_y: f64, end_x: f64, end_y: f64, layer: String, width: f64) {
+
+        info!("添加直线元素: 从({:.6},{:.6})到({:.6},{:.6})，层{}，线宽{:.6}", mil_to_mm(start_x), mil_to_mm(start_y), mil_to_mm(end_x), mil_to_mm(end_y), layer, mil_to_mm(width)); // 添加日志
+        self.graphic_elements.push(format!("  (fp_line (start {:.6} {:.6}) (end {:.6} {:.6}) (layer \"{}\") (width {:.6}) (tstamp {}))", 
+            mil_to_mm(start_x), mil_to_mm(start_y), mil_to_mm(end_x), mil_to_mm(end_y), layer, mil_to_mm(width), Uuid::new_v4()));
+    }
+
+    
+    /// 添加图形元素：圆
+    /// 
+    /// # 参数
+    ///
+    /// * `element` - 图形元素字符串
+    ///
+    /// 示例：(fp_circle (center x y) (end x2 y2) (layer "Layer") (width w) (fill none|solid) ...)
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_graphic_element_circle(&mut self, center_x: f64, center_y: f64, end_x: f64, end_y: f64, layer: String, width: f64, fill: bool) {
+        info!("添加圆形元素: 圆心({:.6},{:.6})，终点({:.6},{:.6})，层{}，线宽{:.6}，填充{}", mil_to_mm(center_x), mil_to_mm(center_y), mil_to_mm(end_x), mil_to_mm(end_y), layer, mil_to_mm(width), if fill { "solid" } else { "none" }); // 添加日志
+        self.graphic_elements.push(format!("  (fp_circle (center {:.6} {:.6}) (end {:.6} {:.6}) (layer {}) (width {:.6}) (fill {}) (tstamp {}))", 
+            mil_to_mm(center_x), - mil_to_mm(center_y), mil_to_mm(end_x), - mil_to_mm(end_y), layer, mil_to_mm(width), if fill { "solid" } else { "none" }, Uuid::new_v4()));
+    }
+
+
+    /// 添加图形元素：矩形
+    ///
+    /// # 参数
+    ///
+    /// * `start_x` - 一个角的X坐标
+    /// * `start_y` - 一个角的Y坐标
+    /// * `end_x` - 对角的X坐标
+    /// * `end_y` - 对角的Y坐标
+    /// * `layer` - 所在层
+    /// * `width` - 线宽
+    /// * `fill` - 是否填充
+    ///
+    /// 示例：(fp_rect (start x y) (end x2 y2) (layer "Layer") (width w) (fill none|solid) ...)
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_graphic_element_rect(&mut self, start_x: f64, start_y: f64, end_x: f64, end_y: f64, layer: String, width: f64, fill: bool) {
+        info!("添加矩形元素: 从({:.6},{:.6})到({:.6},{:.6})，层{}，线宽{:.6}，填充{}", mil_to_mm(start_x), mil_to_mm(start_y), mil_to_mm(end_x), mil_to_mm(end_y), layer, mil_to_mm(width), fill);
+        self.graphic_elements.push(format!("  (fp_rect (start {:.6} {:.6}) (end {:.6} {:.6}) (layer \"{}\") (width {:.6}) (fill {}) (tstamp {}))",
+            mil_to_mm(start_x), - mil_to_mm(start_y), mil_to_mm(end_x), - mil_to_mm(end_y), layer, mil_to_mm(width), if fill { "solid" } else { "none" }, Uuid::new_v4()));
+    }
+
+
+    /// 添加图形元素:多边形
+    /// 
+    /// # 参数
+    ///
+    /// * `element` - 图形元素字符串
+    ///
+    /// 示例：(fp_poly (pts (xy -16.1036 4.953) (xy -16.8656 4.191) ...) (layer "F.SilkS") (width 0.12) (fill solid) (tstamp {}))
+    pub fn add_graphic_element_polygon(&mut self, pts: &[f64], layer: String, width: f64, fill: bool) {
+        // 如果多边形在F.SilkS层，需要使用add_graphic_element_line来将多边形分成一段段的直线
+        if layer == "F.SilkS" && !fill {
+            // 将多边形分解为线段
+            for i in 0..((pts.len()/2)-1) {
+                let start_x = pts[i*2];
+                let start_y = pts[i*2+1];
+                let end_x = pts[(i+1)*2];
+                let end_y = pts[(i+1)*2+1];   
+                self.add_graphic_element_line(start_x, -start_y, end_x, -end_y, layer.clone(), width);
+            }
+        } else {
+            let mut str_pts = "  (fp_poly (pts ".to_string();
+            // 获取pts长度
+        
+            for i in 0..pts.len()/2 {
+                str_pts.push_str(&format!("   (xy {:.6} {:.6})", mil_to_mm(pts[i*2]), - mil_to_mm(pts[i*2+1])));
+            }
+            str_pts.push_str(&format!("  ) (layer \"{}\") (width {:.6}) (fill {}) (tstamp {}))", layer, mil_to_mm(width), if fill { "solid" } else { "none" }, Uuid::new_v4()));
+            info!("添加多边形元素: {};", str_pts); 
+            self.graphic_elements.push(str_pts);
+        }
+    }
+
+
+    /// 添加图形元素:带圆弧的多边形，需要KiCad 7及以上
+    ///
+    /// # 参数
+    ///
+    /// * `start_x` - 起点的X坐标
+    /// * `start_y` - 起点的Y坐标
+    /// * `segments` - 从起点开始的各段直线、圆弧
+    /// * `layer` - 层
+    /// * `width` - 线宽
+    /// * `fill` - 是否填充
+    ///
+    /// 示例：(fp_poly (pts (xy 0 0) (arc (start 0 0) (mid 1 1) (end 2 0)) ...) (layer "F.Cu") (width 0) (fill solid) (tstamp {}))
+    pub fn add_graphic_element_polygon_arcs(&mut self, start_x: f64, start_y: f64, segments: &[OutlineSegment], layer: String, width: f64, fill: bool) {
+        const EPSILON: f64 = 1e-6;
+        let mut str_pts = "  (fp_poly (pts ".to_string();
+        // 圆弧的起点就是多边形的顶点，所以顶点先暂存，下一段是圆弧时丢弃，避免重复输出
+        let mut pending = Some((start_x, start_y));
+        let push_xy = |str_pts: &mut String, pending: Option<(f64, f64)>| {
+            if let Some((x, y)) = pending {
+                str_pts.push_str(&format!("   (xy {:.6} {:.6})", mil_to_mm(x), - mil_to_mm(y)));
+            }
+        };
+        let (mut last_x, mut last_y) = (start_x, start_y);
+        for segment in segments {
+            let (x, y) = match *segment {
+                OutlineSegment::Line { x, y } | OutlineSegment::Arc { x, y, .. } => (x, y),
+            };
+            let arc = match *segment {
+                OutlineSegment::Arc { angle, .. } => {
+                    let (sx, sy, mid_x, mid_y, ex, ey) = calculate_arc_midpoint(last_x, -last_y, x, -y, -angle);
+                    // 角度为0或起点终点重合时按直线处理
+                    ((mid_x - sx).hypot(mid_y - sy) >= 1e-10).then_some((sx, sy, mid_x, mid_y, ex, ey))
+                }
+                OutlineSegment::Line { .. } => None,
+            };
+            match arc {
+                Some((sx, sy, mid_x, mid_y, ex, ey)) => {
+                    // calculate_arc_midpoint统一按逆时针返回，起点终点可能被交换，这里恢复路径的方向
+                    let ((sx, sy), (ex, ey)) = if (sx - last_x).hypot(sy + last_y) < 1e-10 {
+                        ((sx, sy), (ex, ey))
+                    } else {
+                        ((ex, ey), (sx, sy))
+                    };
+                    pending = None;
+                    str_pts.push_str(&format!("   (arc (start {:.6} {:.6}) (mid {:.6} {:.6}) (end {:.6} {:.6}))",
+                        mil_to_mm(sx), mil_to_mm(sy), mil_to_mm(mid_x), mil_to_mm(mid_y), mil_to_mm(ex), mil_to_mm(ey)));
+                }
+                // 长度为0的线段不输出
+                None if (x - last_x).hypot(y - last_y) >= EPSILON => {
+                    push_xy(&mut str_pts, pending);
+                    pending = Some((x, y));
+                }
+                None => {}
+            }
+            (last_x, last_y) = (x, y);
+        }
+        push_xy(&mut str_pts, pending);
+        str_pts.push_str(&format!("  ) (layer \"{}\") (width {:.6}) (fill {}) (tstamp {}))", layer, mil_to_mm(width), if fill { "solid" } else { "none" }, Uuid::new_v4()));
+        info!("添加带圆弧的多边形元素: {};", str_pts);
+        self.graphic_elements.push(str_pts);
+    }
+
+
+    /// 添加图形元素：圆弧
+    /// 
+    /// # 参数
+    ///
+    /// * `element` - 图形元素字符串
+    ///
+    /// 示例：(fp_arc (start x1 y1) (mid x2 y2) (end x3 y3) (layer "LayerName") (width w) (tstamp ...))
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_graphic_element_arc(&mut self, start_x: f64, start_y: f64, angle: f64, end_x: f64, end_y: f64, layer: String, width: f64) {
+        let (start_x_3,start_y_3,mid_x, mid_y,end_x_3,end_y_3) = calculate_arc_midpoint(start_x, -start_y, end_x, -end_y, -angle);
+        info!("添加圆弧元素: 起点({:.6},{:.6})，中点({:.6},{:.6})，终点({:.6},{:.6})，层{}，线宽{:.6}", mil_to_mm(start_x_3), mil_to_mm(start_y_3), mil_to_mm(mid_x), mil_to_mm(mid_y), mil_to_mm(end_x_3), mil_to_mm(end_y_3), layer, mil_to_mm(width)); // 添加日志
+        self.graphic_elements.push(format!("  (fp_arc (start {:.6} {:.6}) (mid {:.6} {:.6}) (end {:.6} {:.6}) (layer {}) (width {:.6})(tstamp {}))", 
+            mil_to_mm(start_x_3), mil_to_mm(start_y_3), mil_to_mm(mid_x), mil_to_mm(mid_y), mil_to_mm(end_x_3), mil_to_mm(end_y_3), layer, mil_to_mm(width), Uuid::new_v4()));    
+        
+    }
+
+    /// 添加非金属化孔，焊盘大小与钻孔相同
+    ///
+    /// # 参数
+    /// * `pad_name` - 焊盘名称，通常为空
+    /// * `center_x` - 中心的X坐标
+    /// * `center_y` - 中心的Y坐标
+    /// * `angle` - 角度
+    /// * `drill_x` - 钻孔宽度
+    /// * `drill_y` - 钻孔高度，与宽度相同时为圆孔，否则为槽孔
+    pub fn add_pad_np_hole(&mut self, pad_name: &str, center_x: f64, center_y: f64, angle: f64, drill_x: f64, drill_y: f64) {
+        info!("添加非金属化孔元素: 中心({:.6},{:.6})，角度{}，钻孔{:.6}x{:.6}", mil_to_mm(center_x), mil_to_mm(center_y), angle, mil_to_mm(drill_x), mil_to_mm(drill_y));
+        if drill_x == drill_y {
+            self.pads.push(format!("  (pad \"{}\" np_thru_hole circle (at {:.6} {:.6}) (size {:.6} {:.6}) (drill {:.6}) (layers {}) (tstamp {}))",
+                escape_string(pad_name), mil_to_mm(center_x), - mil_to_mm(center_y), mil_to_mm(drill_x), mil_to_mm(drill_x), mil_to_mm(drill_x), "F&B.Cu *.Mask", Uuid::new_v4()));
+        } else {
+            self.pads.push(format!("  (pad \"{}\" np_thru_hole oval (at {:.6} {:.6} {}) (size {:.6} {:.6}) (drill oval {:.6} {:.6}) (layers {}) (tstamp {}))",
+                escape_string(pad_name), mil_to_mm(center_x), - mil_to_mm(center_y), angle, mil_to_mm(drill_x), mil_to_mm(drill_y), mil_to_mm(drill_x), mil_to_mm(drill_y), "F&B.Cu *.Mask", Uuid::new_v4()));
+        }
+    }
+
+    /// 添加文本
+    ///
+    /// # 参数
+    /// * `text` - 文本内容
+    /// * `x` - 锚点的X坐标
+    /// * `y` - 锚点的Y坐标
+    /// * `angle` - 角度
+    /// * `layer` - 所在层
+    /// * `height` - 字高
+    /// * `thickness` - 笔画宽度
+    /// * `justify` - 对齐方式，如["left", "top"]，为空时居中
+    /// * `mirror` - 是否镜像
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_text(&mut self, text: &str, x: f64, y: f64, angle: f64, layer: &str, height: f64, thickness: f64, justify: &[&str], mirror: bool) {
+        info!("添加文本元素: {}，位置({:.6},{:.6})，角度{}，层{}，字高{:.6}，笔画宽度{:.6}，对齐{:?}，镜像{}", text, mil_to_mm(x), mil_to_mm(y), angle, layer, mil_to_mm(height), mil_to_mm(thickness), justify, mirror);
+        let mut justify: Vec<&str> = justify.to_vec();
+        if mirror {
+            justify.push("mirror");
+        }
+        let justify = if justify.is_empty() {
+            String::new()
+        } else {
+            format!(" (justify {})", justify.join(" "))
+        };
+        self.text_elements.push(format!("  (fp_text user \"{}\" (at {:.6} {:.6} {}) (layer \"{}\")\n    (effects (font (size {:.6} {:.6}) (thickness {:.6})){})\n    (tstamp {}))",
+            escape_string(text), mil_to_mm(x), - mil_to_mm(y), angle, layer, mil_to_mm(height), mil_to_mm(height), mil_to_mm(thickness), justify, Uuid::new_v4()));
+    }
+
+    /// 添加圆形贴片焊盘
+    /// 
+    /// # 参数
+    /// * `pad_name` - 焊盘名称
+    /// * `geometry` - 圆心坐标和直径，角度和高度不使用
+    /// * `layers` - 焊盘所在的铜、锡膏、阻焊层，如"F.Cu" "F.Paste" "F.Mask"
+    /// * `margins` - 阻焊和锡膏距离
+    /// 
+    pub fn add_pad_circle(&mut self, pad_name: &str, geometry: &PadGeometry, layers: &str, margins: &PadMargins) {
+        let PadGeometry { center_x, center_y, width: diameter, .. } = *geometry;
+        let PadMargins { solder_mask: solder_mask_margin, solder_paste: solder_paste_margin } = *margins;
+        info!("添加圆形贴片焊盘元素: 圆心({:.6},{:.6})，直径{:.6}，层{}，阻焊距离{}，锡膏距离{}", mil_to_mm(center_x), mil_to_mm(center_y), mil_to_mm(diameter), layers, solder_mask_margin, solder_paste_margin); 
+        self.has_smd_pad = true;
+        self.pads.push(format!("  (pad \"{}\" smd circle (at {:.6} {:.6}) (size {:.6} {:.6}) (layers {}) (solder_mask_margin {:.6}) (solder_paste_margin {:.6}) (tstamp {}))", 
+            escape_string(pad_name), mil_to_mm(center_x), - mil_to_mm(center_y), mil_to_mm(diameter), mil_to_mm(diameter), layers, mil_to_mm(solder_mask_margin), mil_to_mm(solder_paste_margin), Uuid::new_v4()));  
+    }
+
+    /// 添加矩形贴片焊盘
+    /// 
+    /// # 参数
+    /// * `pad_name` - 焊盘名称
+    /// * `geometry` - 中心坐标、角度、宽度和高度
+    /// * `layers` - 焊盘所在的铜、锡膏、阻焊层，如"F.Cu" "F.Paste" "F.Mask"
+    /// * `margins` - 阻焊和锡膏距离
+    pub fn add_pad_rect(&mut self, pad_name: &str, geometry: &PadGeometry, layers: &str, margins: &PadMargins) {
+        let PadGeometry { center_x, center_y, angle, width, height } = *geometry;
+        let PadMargins { solder_mask: solder_mask_margin, solder_paste: solder_paste_margin } = *margins;
+        info!("添加矩形贴片焊盘元素: 圆心({:.6},{:.6})，角度{}，宽度{:.6}，高度{:.6}，层{}，阻焊距离{}，锡膏距离{}", mil_to_mm(center_x), mil_to_mm(center_y), angle, mil_to_mm(width), mil_to_mm(height), layers, solder_mask_margin, solder_paste_margin); 
+        self.has_smd_pad = true;
+        self.pads.push(format!("  (pad \"{}\" smd rect (at {:.6} {:.6} {}) (size {:.6} {:.6}) (layers {}) (solder_mask_margin {:.6}) (solder_paste_margin {:.6}) (tstamp {}))", 
+            escape_string(pad_name), mil_to_mm(center_x), - mil_to_mm(center_y), angle, mil_to_mm(width), mil_to_mm(height), layers, mil_to_mm(solder_mask_margin), mil_to_mm(solder_paste_margin), Uuid::new_v4()));  
+    }
+
+    /// 添加圆角矩形贴片焊盘
+    ///
+    /// # 参数
+    /// * `pad_name` - 焊盘名称
+    /// * `geometry` - 中心坐标、角度、宽度和高度
+    /// * `rratio` - 圆角半径与短边之比，最大0.5
+    /// * `layers` - 焊盘所在的铜、锡膏、阻焊层，如"F.Cu" "F.Paste" "F.Mask"
+    /// * `margins` - 阻焊和锡膏距离
+    pub fn add_pad_roundrect(&mut self, pad_name: &str, geometry: &PadGeometry, rratio: f64, layers: &str, margins: &PadMargins) {
+        let PadGeometry { center_x, center_y, angle, width, height } = *geometry;
+        let PadMargins { solder_mask: solder_mask_margin, solder_paste: solder_paste_margin } = *margins;
+        info!("添加圆角矩形贴片焊盘元素: 中心({:.6},{:.6})，角度{}，宽度{:.6}，高度{:.6}，圆角比例{}，层{}，阻焊距离{}，锡膏距离{}", mil_to_mm(center_x), mil_to_mm(center_y), angle, mil_to_mm(width), mil_to_mm(height), rratio, layers, solder_mask_margin, solder_paste_margin);
+        self.has_smd_pad = true;
+        self.pads.push(format!("  (pad \"{}\" smd roundrect (at {:.6} {:.6} {}) (size {:.6} {:.6}) (layers {}) (roundrect_rratio {:.6}) (solder_mask_margin {:.6}) (solder_paste_margin {:.6}) (tstamp {}))",
+            escape_string(pad_name), mil_to_mm(center_x), - mil_to_mm(center_y), angle, mil_to_mm(width), mil_to_mm(height), layers, rratio, mil_to_mm(solder_mask_margin), mil_to_mm(solder_paste_margin), Uuid::new_v4()));
+    }
+
+    /// 添加切角矩形贴片焊盘
+    ///
+    /// # 参数
+    /// * `pad_name` - 焊盘名称
+    /// * `geometry` - 中心坐标、角度、宽度和高度
+    /// * `chamfer_ratio` - 切角长度与短边之比，最大0.5
+    /// * `corners` - 切角的位置，可选top_left、top_right、bottom_left、bottom_right
+    /// * `layers` - 焊盘所在的铜、锡膏、阻焊层，如"F.Cu" "F.Paste" "F.Mask"
+    /// * `margins` - 阻焊和锡膏距离
+    pub fn add_pad_chamfered_rect(&mut self, pad_name: &str, geometry: &PadGeometry, chamfer_ratio: f64, corners: &[&str], layers: &str, margins: &PadMargins) {
+        let PadGeometry { center_x, center_y, angle, width, height } = *geometry;
+        let PadMargins { solder_mask: solder_mask_margin, solder_paste: solder_paste_margin } = *margins;
+        info!("添加切角矩形贴片焊盘元素: 中心({:.6},{:.6})，角度{}，宽度{:.6}，高度{:.6}，切角比例{}，切角{:?}，层{}，阻焊距离{}，锡膏距离{}", mil_to_mm(center_x), mil_to_mm(center_y), angle, mil_to_mm(width), mil_to_mm(height), chamfer_ratio, corners, layers, solder_mask_margin, solder_paste_margin);
+        // KiCad中切角矩形的形状名称也是roundrect
+        self.has_smd_pad = true;
+        self.pads.push(format!("  (pad \"{}\" smd roundrect (at {:.6} {:.6} {}) (size {:.6} {:.6}) (layers {}) (roundrect_rratio 0) (chamfer_ratio {:.6}) (chamfer {}) (solder_mask_margin {:.6}) (solder_paste_margin {:.6}) (tstamp {}))",
+            escape_string(pad_name), mil_to_mm(center_x), - mil_to_mm(center_y), angle, mil_to_mm(width), mil_to_mm(height), layers, chamfer_ratio, corners.join(" "), mil_to_mm(solder_mask_margin), mil_to_mm(solder_paste_margin), Uuid::new_v4()));
+    }
+
+    /// 添加椭圆形贴片焊盘
+    /// 
+    /// # 参数
+    /// * `pad_name` - 焊盘名称
+    /// * `geometry` - 中心坐标、角度、宽度和高度
+    /// * `layers` - 焊盘所在的铜、锡膏、阻焊层，如"F.Cu" "F.Paste" "F.Mask"
+    /// * `margins` - 阻焊和锡膏距离
+    pub fn add_pad_ellipse(&mut self, pad_name: &str, geometry: &PadGeometry, layers: &str, margins: &PadMargins) {
+        let PadGeometry { center_x, center_y, angle, width, height } = *geometry;
+        let PadMargins { solder_mask: solder_mask_margin, solder_paste: solder_paste_margin } = *margins;
+        info!("添加椭圆形贴片焊盘元素: 圆心({:.6},{:.6})，宽度{:.6}，高度{:.6}，层{}，角度{}，阻焊距离{}，锡膏距离{}", mil_to_mm(center_x), mil_to_mm(center_y), mil_to_mm(width), mil_to_mm(height), layers, angle, solder_mask_margin, solder_paste_margin); 
+        self.has_smd_pad = true;
+        self.pads.push(format!("  (pad \"{}\" smd oval (at {:.6} {:.6} {}) (size {:.6} {:.6}) (layers {}) (solder_mask_margin {:.6}) (solder_paste_margin {:.6}) (tstamp {}))", 
+            escape_string(pad_name), mil_to_mm(center_x), - mil_to_mm(center_y), angle, mil_to_mm(width), mil_to_mm(height), layers, mil_to_mm(solder_mask_margin), mil_to_mm(solder_paste_margin), Uuid::new_v4()));  
+    }
+
+    /// 添加多边形焊盘
+    /// 
+    /// # 参数
+    /// * `pad_name` - 焊盘名称
+    /// * `center_x` - 中心的X坐标
+    /// * `center_y` - 中心的Y坐标
+    /// * `pts` - 点的坐标，格式为[x1, y1, x2, y2, ...]
+    /// * `layers` - 焊盘所在的铜、锡膏、阻焊层，如"F.Cu" "F.Paste" "F.Mask"
+    /// * `margins` - 阻焊和锡膏距离
+    pub fn add_pad_poly(&mut self, pad_name: &str, center_x: f64, center_y: f64, pts: &[f64], layers: &str, margins: &PadMargins) {
+        let PadMargins { solder_mask: solder_mask_margin, solder_paste: solder_paste_margin } = *margins;
+        let mut str_pts = String::new();
+        str_pts.push_str(&format!("  (pad \"{}\" smd custom (at {:.6} {:.6}) (size 0.0001 0.0001) (layers {})\n    (options (clearance outline) (anchor circle))\n    (primitives\n      (gr_poly (pts", 
+            escape_string(pad_name), mil_to_mm(center_x), - mil_to_mm(center_y), layers));
+        
+        let relative_pts = absolute_to_relative(pts, center_x, center_y);
+        for i in 0..relative_pts.len() / 2 {
+            str_pts.push_str(&format!(" (xy {:.6} {:.6})", mil_to_mm(relative_pts[i * 2]), mil_to_mm(relative_pts[i * 2 + 1])));
+        }
+        str_pts.push_str(&format!("\n      ) (width 0) (fill yes))\n  )(solder_mask_margin {:.6}) (solder_paste_margin {:.6})(tstamp {}))", mil_to_mm(solder_mask_margin), mil_to_mm(solder_paste_margin), Uuid::new_v4()));
+        info!("添加多边形焊盘元素: 中心({:.6},{:.6})，点{}，层{}，阻焊距离{}，锡膏距离{}", mil_to_mm(center_x), mil_to_mm(center_y), str_pts, layers, solder_mask_margin, solder_paste_margin);
+        self.has_smd_pad = true;
+        self.pads.push(str_pts);
+    }
+
+
+    /// 添加通孔圆形焊盘
+    /// 
+    /// # 参数
+    /// * `pad_name` - 焊盘名称
+    /// * `geometry` - 圆心坐标、角度和直径，高度不使用
+    /// * `drill` - 钻孔，圆孔或槽孔
+    pub fn add_pad_hole(&mut self, pad_name: &str, geometry: &PadGeometry, drill: &PadDrill) {
+        let PadGeometry { center_x, center_y, angle, width: diameter, .. } = *geometry;
+        info!("添加通孔圆形焊盘元素: 圆心({:.6},{:.6})，直径{:.6}，层{}，角度{}，钻孔{:?}", mil_to_mm(center_x), mil_to_mm(center_y), mil_to_mm(diameter), "F.Cu", angle, drill);
+        self.has_thru_hole_pad = true;
+        self.pads.push(format!("  (pad \"{}\" thru_hole circle (at {:.6} {:.6} {}) (size {:.6} {:.6}) {} (layers *.Cu *.Mask) (solder_mask_margin 0.051) (tstamp {}))",
+            escape_string(pad_name), mil_to_mm(center_x), - mil_to_mm(center_y), angle, mil_to_mm(diameter), mil_to_mm(diameter), drill.generate(), Uuid::new_v4()));
+    }
+
+    /// 添加通孔椭圆焊盘
+    /// 
+    /// # 参数
+    /// * `pad_name` - 焊盘名称
+    /// * `geometry` - 中心坐标、角度、宽度和高度
+    /// * `drill` - 钻孔，圆孔或槽孔
+    pub fn add_pad_hole_oval(&mut self, pad_name: &str, geometry: &PadGeometry, drill: &PadDrill) {
+        let PadGeometry { center_x, center_y, angle, width, height } = *geometry;
+        info!("添加通孔椭圆焊盘元素: 中心({:.6},{:.6})，宽度{:.6}，高度{:.6}，层{}，角度{}，钻孔{:?}", mil_to_mm(center_x), mil_to_mm(center_y), mil_to_mm(width), mil_to_mm(height), "F.Cu", angle, drill);
+        self.has_thru_hole_pad = true;
+        self.pads.push(format!("  (pad \"{}\" thru_hole oval (at {:.6} {:.6} {}) (size {:.6} {:.6}) {} (layers *.Cu *.Mask) (solder_mask_margin 0.051) (tstamp {}))",
+            escape_string(pad_name), mil_to_mm(center_x), - mil_to_mm(center_y), angle, mil_to_mm(width), mil_to_mm(height), drill.generate(), Uuid::new_v4()));
+    }
+    
+    ///添加通孔矩形焊盘
+    /// 
+    /// # 参数
+    /// * `pad_name` - 焊盘名称
+    /// * `geometry` - 中心坐标、角度、宽度和高度
+    /// * `drill` - 钻孔，圆孔或槽孔
+    pub fn add_pad_hole_rect(&mut self, pad_name: &str, geometry: &PadGeometry, drill: &PadDrill) {
+        let PadGeometry { center_x, center_y, angle, width, height } = *geometry;
+        info!("添加通孔矩形焊盘元素: 中心({:.6},{:.6})，宽度{:.6}，高度{:.6}，层{}，角度{}，钻孔{:?}", mil_to_mm(center_x), mil_to_mm(center_y), mil_to_mm(width), mil_to_mm(height), "F.Cu", angle, drill);
+        self.has_thru_hole_pad = true;
+        self.pads.push(format!("  (pad \"{}\" thru_hole rect (at {:.6} {:.6} {}) (size {:.6} {:.6}) {} (layers *.Cu *.Mask) (solder_mask_margin 0.051) (tstamp {}))",
+            escape_string(pad_name), mil_to_mm(center_x), - mil_to_mm(center_y), angle, mil_to_mm(width), mil_to_mm(height), drill.generate(), Uuid::new_v4()));
+    }
+
+    /// 添加通孔圆角矩形焊盘
+    /// 
+    /// # 参数
+    /// * `pad_name` - 焊盘名称
+    /// * `geometry` - 中心坐标、角度、宽度和高度
+    /// * `rratio` - 圆角半径与短边之比，最大0.5
+    /// * `drill` - 钻孔，圆孔或槽孔
+    pub fn add_pad_hole_roundrect(&mut self, pad_name: &str, geometry: &PadGeometry, rratio: f64, drill: &PadDrill) {
+        let PadGeometry { center_x, center_y, angle, width, height } = *geometry;
+        info!("添加通孔圆角矩形焊盘元素: 中心({:.6},{:.6})，宽度{:.6}，高度{:.6}，圆角比例{}，层{}，角度{}，钻孔{:?}", mil_to_mm(center_x), mil_to_mm(center_y), mil_to_mm(width), mil_to_mm(height), rratio, "F.Cu", angle, drill);
+        self.has_thru_hole_pad = true;
+        self.pads.push(format!("  (pad \"{}\" thru_hole roundrect (at {:.6} {:.6} {}) (size {:.6} {:.6}) {} (layers *.Cu *.Mask) (roundrect_rratio {:.6}) (solder_mask_margin 0.051) (tstamp {}))",
+            escape_string(pad_name), mil_to_mm(center_x), - mil_to_mm(center_y), angle, mil_to_mm(width), mil_to_mm(height), drill.generate(), rratio, Uuid::new_v4()));
+    }
+
+    /// 添加通孔多边形焊盘，圆孔或槽孔
+    ///
+    /// # 参数
+    /// * `pad_name` - 焊盘名称
+    /// * `geometry` - 中心坐标、角度和锚点焊盘的大小，槽孔随焊盘旋转，锚点焊盘不能大于钻孔
+    /// * `pts` - 点的坐标，格式为[x1, y1, x2, y2, ...]，为旋转后的绝对坐标
+    /// * `drill` - 钻孔，圆孔或槽孔
+    pub fn add_pad_hole_poly(&mut self, pad_name: &str, geometry: &PadGeometry, pts: &[f64], drill: &PadDrill) {
+        let PadGeometry { center_x, center_y, angle, width, height } = *geometry;
+        let drill = drill.generate();
+        // 铜皮形状由gr_poly决定
+        let mut str_pts = String::new();
+        str_pts.push_str(&format!("  (pad \"{}\" thru_hole custom (at {:.6} {:.6} {}) (size {:.6} {:.6}) {} (layers *.Cu *.Mask)\n    (options (clearance outline) (anchor circle))\n    (primitives\n      (gr_poly (pts",
+            escape_string(pad_name), mil_to_mm(center_x), - mil_to_mm(center_y), angle, mil_to_mm(width), mil_to_mm(height), drill));
+
+        // 多边形的点是旋转后的坐标，转回焊盘自身的坐标系
+        let (sin, cos) = angle.to_radians().sin_cos();
+        let relative_pts = absolute_to_relative(pts, center_x, center_y);
+        for i in 0..relative_pts.len() / 2 {
+            let (x, y) = (relative_pts[i * 2], relative_pts[i * 2 + 1]);
+            str_pts.push_str(&format!(" (xy {:.6} {:.6})", mil_to_mm(x * cos - y * sin), mil_to_mm(x * sin + y * cos)));
+        }
+        str_pts.push_str(&format!("\n      ) (width 0) (fill yes))\n  ) (solder_mask_margin 0.051) (tstamp {}))", Uuid::new_v4()));
+        info!("添加通孔多边形焊盘元素: 中心({:.6},{:.6})，角度{}，点{}，钻孔{}", mil_to_mm(center_x), mil_to_mm(center_y), angle, str_pts, drill);
+        self.has_thru_hole_pad = true;
+        self.pads.push(str_pts);
+    }
+
+    /// 添加过孔，输出为只在铜层上的通孔焊盘，不开阻焊窗
+    ///
+    /// 过孔不影响元件类型的判断，带散热过孔的贴片封装仍为smd
+    ///
+    /// # 参数
+    /// * `pad_name` - 焊盘名称，为空时不连接任何焊盘
+    /// * `center_x` - 圆心的X坐标
+    /// * `center_y` - 圆心的Y坐标
+    /// * `diameter` - 外径
+    /// * `drill` - 钻孔直径
+    pub fn add_pad_via(&mut self, pad_name: &str, center_x: f64, center_y: f64, diameter: f64, drill: f64) {
+        info!("添加过孔元素: 圆心({:.6},{:.6})，外径{:.6}，钻孔直径{:.6}，焊盘{}", mil_to_mm(center_x), mil_to_mm(center_y), mil_to_mm(diameter), mil_to_mm(drill), pad_name);
+        self.pads.push(format!("  (pad \"{}\" thru_hole circle (at {:.6} {:.6}) (size {:.6} {:.6}) (drill {:.6}) (layers *.Cu) (zone_connect 2) (tstamp {}))",
+            escape_string(pad_name), mil_to_mm(center_x), - mil_to_mm(center_y), mil_to_mm(diameter), mil_to_mm(diameter), mil_to_mm(drill), Uuid::new_v4()));
+    }
+
+    /// 添加禁止区域
+    ///
+    /// # 参数
+    /// * `layers` - 所在的铜层，如"F.Cu"、"F.Cu" "B.Cu"
+    /// * `pts` - 轮廓点的坐标，格式为[x1, y1, x2, y2, ...]
+    /// * `not_allowed` - 禁止的类型，可选tracks、vias、pads、copperpour、footprints
+    pub fn add_keepout_zone(&mut self, layers: &str, pts: &[f64], not_allowed: &[&str]) {
+        let keepout = ["tracks", "vias", "pads", "copperpour", "footprints"]
+            .iter()
+            .map(|kind| format!("({} {})", kind, if not_allowed.contains(kind) { "not_allowed" } else { "allowed" }))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let mut zone = format!("  (zone (net 0) (net_name \"\") (layers {}) (tstamp {}) (hatch edge 0.508)\n    (connect_pads (clearance 0))\n    (min_thickness 0.254)\n    (keepout {})\n    (fill (thermal_gap 0.508) (thermal_bridge_width 0.508))\n    (polygon\n      (pts",
+            layers, Uuid::new_v4(), keepout);
+        for i in 0..pts.len() / 2 {
+            zone.push_str(&format!(" (xy {:.6} {:.6})", mil_to_mm(pts[i * 2]), - mil_to_mm(pts[i * 2 + 1])));
+        }
+        zone.push_str("\n      )\n    )\n  )");
+        info!("添加禁止区域元素: 层{}，禁止{:?}，{}个点", layers, not_allowed, pts.len() / 2);
+        self.zones.push(zone);
+    }
+
+    /// 设置参考标识的位置和大小
+    ///
+    /// # 参数
+    /// * `x` - X坐标
+    /// * `y` - Y坐标
+    /// * `angle` - 角度
+    /// * `height` - 字高
+    /// * `thickness` - 笔画宽度
+    pub fn set_reference_position(&mut self, x: f64, y: f64, angle: f64, height: f64, thickness: f64) {
+        info!("设置参考标识位置: ({:.6},{:.6})，角度{}，字高{:.6}", mil_to_mm(x), mil_to_mm(y), angle, mil_to_mm(height));
+        self.reference_field.set_position(x, y, angle, height, thickness);
+    }
+
+    /// 设置参考标识所在层和是否隐藏，底层的文本镜像显示
+    ///
+    /// # 参数
+    /// * `layer` - 所在层，如"F.SilkS"、"B.SilkS"
+    /// * `hide` - 是否隐藏
+    pub fn set_reference_layer(&mut self, layer: &str, hide: bool) {
+        info!("设置参考标识所在层: {}，隐藏{}", layer, hide);
+        self.reference_field.layer = layer.to_string();
+        self.reference_field.hide = hide;
+    }
+
+    /// 设置值的位置和大小
+    ///
+    /// # 参数
+    /// * `x` - X坐标
+    /// * `y` - Y坐标
+    /// * `angle` - 角度
+    /// * `height` - 字高
+    /// * `thickness` - 笔画宽度
+    pub fn set_value_position(&mut self, x: f64, y: f64, angle: f64, height: f64, thickness: f64) {
+        info!("设置值位置: ({:.6},{:.6})，角度{}，字高{:.6}", mil_to_mm(x), mil_to_mm(y), angle, mil_to_mm(height));
+        self.value_field.set_position(x, y, angle, height, thickness);
+    }
+
+    /// 设置值所在层，底层的文本镜像显示
+    ///
+    /// # 参数
+    /// * `layer` - 所在层，如"F.Fab"、"B.Fab"
+    pub fn set_value_layer(&mut self, layer: &str) {
+        info!("设置值所在层: {}", layer);
+        self.value_field.layer = layer.to_string();
+    }
+
+    /// 设置是否不出现在坐标文件中
+    ///
+    /// # 参数
+    /// * `exclude` - 为true时不出现在坐标文件中
+    pub fn set_exclude_from_pos_files(&mut self, exclude: bool) {
+        info!("设置不出现在坐标文件中: {}", exclude);
+        self.exclude_from_pos_files = exclude;
+    }
+
+    /// 设置文件格式版本，输出KiCad 7才支持的语法时需要设置为20221018及以上
+    ///
+    /// # 参数
+    /// * `version` - 版本号，格式为YYYYMMDD
+    pub fn set_version(&mut self, version: u32) {
+        info!("设置文件格式版本: {}", version);
+        self.version = format!("(version {})", version);
+    }
+
+    /// 设置是否不出现在BOM中
+    ///
+    /// # 参数
+    /// * `exclude` - 为true时不出现在BOM中
+    pub fn set_exclude_from_bom(&mut self, exclude: bool) {
+        info!("设置不出现在BOM中: {}", exclude);
+        self.exclude_from_bom = exclude;
+    }
+
+    // 元件类型，有通孔焊盘时为through_hole，只有贴片焊盘时为smd，没有焊盘时不指定类型
+    fn attr(&self) -> Option<String> {
+        let mut values = Vec::new();
+        if self.has_thru_hole_pad {
+            values.push("through_hole");
+        } else if self.has_smd_pad {
+            values.push("smd");
+        }
+        if self.exclude_from_pos_files {
+            values.push("exclude_from_pos_files");
+        }
+        if self.exclude_from_bom {
+            values.push("exclude_from_bom");
+        }
+        if values.is_empty() {
+            None
+        } else {
+            Some(format!("(attr {})", values.join(" ")))
+        }
+    }
+
+    /// 生成完整的kicad_mod文件内容
+    ///
+    /// # 返回值
+    ///
+    /// * `String` - 完整的kicad_mod文件内容
+    pub fn generate_content(&self) -> String {
+        let mut content = String::new();
+        
+        // 添加文件头
+        content.push_str(&self.header);
+        content.push('\n');
+        // 添加版本
+        content.push_str(&self.version);
+        content.push('\n');
+        // 添加生成者
+        content.push_str(&self.generator);
+        content.push('\n');
+        // 添加所在层
+        content.push_str(&self.layer);
+        content.push('\n');
+        // 添加最后编辑时间
+        content.push_str(&self.tedit);
+        content.push('\n');
+        // 添加描述
+        content.push_str(&self.description);
+        content.push('\n');
+        // 添加标签
+        content.push_str(&self.tags);
+        content.push('\n');
+        // 添加元件类型，没有类型和标志时不输出
+        if let Some(attr) = self.attr() {
+            content.push_str(&attr);
+            content.push('\n');
+        }
+        // 添加参考标识和值
+        content.push_str(&self.reference_field.generate("reference", "REF**"));
+        content.push('\n');
+        content.push_str(&self.value_field.generate("value", &self.value));
+        content.push('\n');
+        // 添加文本元素
+        for element in &self.text_elements {
+            content.push_str(element);
+            content.push('\n');
+        }
+        
+        // 添加图形元素
+        for element in &self.graphic_elements {
+            content.push_str(element);
+            content.push('\n');
+        }
+        
+        // 添加焊盘信息
+        for pad in &self.pads {
+            content.push_str(pad);
+            content.push('\n');
+        }
+
+        // 添加禁止区域
+        for zone in &self.zones {
+            content.push_str(zone);
+            content.push('\n');
+        }
+        
+        
+        // 添加文件结尾
+        content.push(')');
+        
+        info!("生成kicad_mod文件内容，共{}个文本元素，{}个图形元素，{}个焊盘", self.text_elements.len(), self.graphic_elements.len(), self.pads.len()); // 添加日志
+        
+        content
+    }
+}
+
+/// 符号属性
+#[derive(Debug, Clone)]
+struct SymProperty {
+    /// 属性名
+    key: String,
+    /// 属性值
+    value: String,
+    /// 显示位置
+    x: f64,
+    y: f64,
+    /// 是否显示
+    visible: bool,
+}
+
+/// 生成的kicad_sym文件的格式版本，对应KiCad 6
+const SYMBOL_LIB_VERSION: u32 = 20211014;
+
+/// 从这个版本开始KiCad不再保存符号属性的(id N)
+const SYMBOL_PROPERTY_ID_DROPPED_VERSION: u32 = 20220929;
+
+/// 用于暂存kicad_sym文件中单个符号数据的结构体
+#[derive(Debug, Clone)]
+pub struct KicadSymData {
+    /// 符号名称
+    name: String,
+    /// 属性，前四个依次为Reference、Value、Footprint、Datasheet
+    properties: Vec<SymProperty>,
+    /// 引脚名称偏移，单位mm
+    pin_names_offset: f64,
+    /// 是否隐藏引脚名称
+    pin_names_hide: bool,
+    /// 是否隐藏引脚编号
+    pin_numbers_hide: bool,
+    /// 各单元的图形元素和引脚，键为单元编号，0为所有单元共用
+    units: BTreeMap<u32, Vec<String>>,
+}
+
+impl KicadSymData {
+    /// 创建一个新的KicadSymData实例
+    ///
+    /// # 参数
+    ///
+    /// * `symbol_name` - 符号名称
+    /// * `reference` - 位号前缀，如"U"、"R"
+    ///
+    /// # 返回值
+    ///
+    /// * `KicadSymData` - 新创建的实例
+    pub fn new(symbol_name: &str, reference: &str) -> Self {
+        let property = |key: &str, value: &str, y: f64, visible: bool| SymProperty {
+            key: key.to_string(),
+            value: value.to_string(),
+            x: 0.0,
+            y,
+            visible,
+        };
+        // KiCad要求的四个基本属性
+        let properties = vec![
+            property("Reference", reference, 2.54, true),
+            property("Value", symbol_name, -2.54, true),
+            property("Footprint", "", 0.0, false),
+            property("Datasheet", "", 0.0, false),
+        ];
+
+        info!("创建新的KicadSymData实例: {}", symbol_name); // 添加日志
+
+        KicadSymData {
+            name: symbol_name.to_string(),
+            properties,
+            pin_names_offset: 1.016,
+            pin_names_hide: false,
+            pin_numbers_hide: false,
+            units: BTreeMap::new(),
+        }
+    }
+
+    /// 设置属性，已存在则修改值，否则添加一个隐藏的属性
+    ///
+    /// # 参数
+    ///
+    /// * `key` - 属性名
+    /// * `value` - 属性值
+    pub fn set_property(&mut self, key: &str, value: &str) {
+        info!("设置符号属性: {}={}", key, value);
+        match self.properties.iter_mut().find(|p| p.key == key) {
+            Some(property) => property.value = value.to_string(),
+            None => self.properties.push(SymProperty {
+                key: key.to_string(),
+                value: value.to_string(),
+                x: 0.0,
+                y: 0.0,
+                visible: false,
+            }),
+        }
+    }
+
+    /// 设置属性的显示位置和是否显示，属性不存在时忽略
+    ///
+    /// # 参数
+    ///
+    /// * `key` - 属性名
+    /// * `x`, `y` - 显示位置
+    /// * `visible` - 是否显示
+    pub fn set_property_position(&mut self, key: &str, x: f64, y: f64, visible: bool) {
+        if let Some(property) = self.properties.iter_mut().find(|p| p.key == key) {
+            property.x = sch_to_mm(x);
+            property.y = sch_to_mm(y);
+            property.visible = visible;
+        }
+    }
+
+    /// 设置引脚名称的显示方式
+    ///
+    /// # 参数
+    ///
+    /// * `offset` - 引脚名称偏移，单位mm，为0时名称显示在引脚外侧
+    /// * `hide` - 是否隐藏引脚名称
+    pub fn set_pin_names(&mut self, offset: f64, hide: bool) {
+        self.pin_names_offset = offset;
+        self.pin_names_hide = hide;
+    }
+
+    /// 设置是否隐藏引脚编号
+    pub fn set_pin_numbers_hide(&mut self, hide: bool) {
+        self.pin_numbers_hide = hide;
+    }
+
+    /// 添加引脚
+    ///
+    /// # 参数
+    ///
+    /// * `unit` - 单元编号
+    /// * `pin_type` - 电气类型，如input、output、bidirectional、power_in、open_collector、passive
+    /// * `shape` - 图形样式，如line、inverted、clock、inverted_clock
+    /// * `name` - 引脚名称
+    /// * `number` - 引脚编号
+    /// * `x` - 连接点的X坐标
+    /// * `y` - 连接点的Y坐标
+    /// * `rotation` - 旋转角度
+    /// * `length` - 引脚长度
+    /// * `visible` - 是否显示
+    ///
+    /// 示例：(pin input line (at -10.16 2.54 0) (length 2.54) (name "IN" ...) (number "1" ...))
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_pin(&mut self, unit: u32, pin_type: &str, shape: &str, name: &str, number: &str, x: f64, y: f64, rotation: f64, length: f64, visible: bool) {
+        info!("添加引脚: {}({})，类型{}，样式{}，位置({:.4},{:.4})，角度{}，长度{:.4}", name, number, pin_type, shape, sch_to_mm(x), sch_to_mm(y), rotation, sch_to_mm(length));
+        self.units.entry(unit).or_default().push(format!("      (pin {} {} (at {:.4} {:.4} {}) (length {:.4}){}\n        (name \"{}\" (effects (font (size 1.27 1.27))))\n        (number \"{}\" (effects (font (size 1.27 1.27)))))",
+            pin_type, shape, sch_to_mm(x), sch_to_mm(y), rotation, sch_to_mm(length), if visible { "" } else { " hide" }, escape_string(name), escape_string(number)));
+    }
+
+    /// 添加矩形
+    ///
+    /// # 参数
+    ///
+    /// * `unit` - 单元编号
+    /// * `start_x`, `start_y` - 一个角的坐标
+    /// * `end_x`, `end_y` - 对角的坐标
+    /// * `width` - 线宽
+    /// * `fill` - 是否填充
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_rectangle(&mut self, unit: u32, start_x: f64, start_y: f64, end_x: f64, end_y: f64, width: f64, fill: bool) {
+        info!("添加矩形: ({:.4},{:.4})到({:.4},{:.4})", sch_to_mm(start_x), sch_to_mm(start_y), sch_to_mm(end_x), sch_to_mm(end_y));
+        self.units.entry(unit).or_default().push(format!("      (rectangle (start {:.4} {:.4}) (end {:.4} {:.4})\n        (stroke (width {:.4}) (type default) (color 0 0 0 0))\n        (fill (type {})))",
+            sch_to_mm(start_x), sch_to_mm(start_y), sch_to_mm(end_x), sch_to_mm(end_y), sch_to_mm(width), sym_fill(fill)));
+    }
+
+    /// 添加折线
+    ///
+    /// # 参数
+    ///
+    /// * `unit` - 单元编号
+    /// * `pts` - 点的坐标，格式为[x1, y1, x2, y2, ...]
+    /// * `width` - 线宽
+    /// * `fill` - 是否填充
+    pub fn add_polyline(&mut self, unit: u32, pts: &[f64], width: f64, fill: bool) {
+        let mut str_pts = "      (polyline\n        (pts".to_string();
+        for i in 0..pts.len() / 2 {
+            str_pts.push_str(&format!(" (xy {:.4} {:.4})", sch_to_mm(pts[i * 2]), sch_to_mm(pts[i * 2 + 1])));
+        }
+        str_pts.push_str(&format!(")\n        (stroke (width {:.4}) (type default) (color 0 0 0 0))\n        (fill (type {})))", sch_to_mm(width), sym_fill(fill)));
+        info!("添加折线: {}", str_pts);
+        self.units.entry(unit).or_default().push(str_pts);
+    }
+
+    /// 添加圆
+    ///
+    /// # 参数
+    ///
+    /// * `unit` - 单元编号
+    /// * `center_x`, `center_y` - 圆心坐标
+    /// * `radius` - 半径
+    /// * `width` - 线宽
+    /// * `fill` - 是否填充
+    pub fn add_circle(&mut self, unit: u32, center_x: f64, center_y: f64, radius: f64, width: f64, fill: bool) {
+        info!("添加圆: 圆心({:.4},{:.4})，半径{:.4}", sch_to_mm(center_x), sch_to_mm(center_y), sch_to_mm(radius));
+        self.units.entry(unit).or_default().push(format!("      (circle (center {:.4} {:.4}) (radius {:.4})\n        (stroke (width {:.4}) (type default) (color 0 0 0 0))\n        (fill (type {})))",
+            sch_to_mm(center_x), sch_to_mm(center_y), sch_to_mm(radius), sch_to_mm(width), sym_fill(fill)));
+    }
+
+    /// 添加三点圆弧
+    ///
+    /// # 参数
+    ///
+    /// * `unit` - 单元编号
+    /// * `start_x`, `start_y` - 起点坐标
+    /// * `mid_x`, `mid_y` - 弧上中间点坐标
+    /// * `end_x`, `end_y` - 终点坐标
+    /// * `width` - 线宽
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_arc(&mut self, unit: u32, start_x: f64, start_y: f64, mid_x: f64, mid_y: f64, end_x: f64, end_y: f64, width: f64) {
+        info!("添加圆弧: 起点({:.4},{:.4})，中点({:.4},{:.4})，终点({:.4},{:.4})", sch_to_mm(start_x), sch_to_mm(start_y), sch_to_mm(mid_x), sch_to_mm(mid_y), sch_to_mm(end_x), sch_to_mm(end_y));
+        self.units.entry(unit).or_default().push(format!("      (arc (start {:.4} {:.4}) (mid {:.4} {:.4}) (end {:.4} {:.4})\n        (stroke (width {:.4}) (type default) (color 0 0 0 0))\n        (fill (type none)))",
+            sch_to_mm(start_x), sch_to_mm(start_y), sch_to_mm(mid_x), sch_to_mm(mid_y), sch_to_mm(end_x), sch_to_mm(end_y), sch_to_mm(width)));
+    }
+
+    /// 添加文本
+    ///
+    /// # 参数
+    ///
+    /// * `unit` - 单元编号
+    /// * `text` - 文本内容
+    /// * `x`, `y` - 文本位置
+    /// * `rotation` - 旋转角度
+    /// * `size` - 字号，为None时使用默认字号
+    pub fn add_text(&mut self, unit: u32, text: &str, x: f64, y: f64, rotation: f64, size: Option<f64>) {
+        let size = size.map(sch_to_mm).unwrap_or(1.27);
+        info!("添加文本: {}，位置({:.4},{:.4})，角度{}，字号{:.4}", text, sch_to_mm(x), sch_to_mm(y), rotation, size);
+        // 符号库中文本的角度单位为0.1度
+        self.units.entry(unit).or_default().push(format!("      (text \"{}\" (at {:.4} {:.4} {})\n        (effects (font (size {:.4} {:.4}))))",
+            escape_string(text), sch_to_mm(x), sch_to_mm(y), rotation * 10.0, size, size));
+    }
+
+    /// 生成单个符号的内容，即kicad_sym文件中的一个(symbol ...)条目
+    ///
+    /// # 返回值
+    ///
+    /// * `String` - 以两个空格缩进的(symbol ...)条目，以换行结尾
+    ///
+    /// # 参数
+    ///
+    /// * `version` - 目标库文件的格式版本，较新的版本不输出属性的id
+    pub fn generate_symbol(&self, version: u32) -> String {
+        let mut content = String::new();
+        let name = escape_string(&self.name);
+        content.push_str(&format!("  (symbol \"{}\"", name));
+        if self.pin_numbers_hide {
+            content.push_str(" (pin_numbers hide)");
+        }
+        content.push_str(&format!(" (pin_names (offset {:.4}){})", self.pin_names_offset, if self.pin_names_hide { " hide" } else { "" }));
+        content.push_str(" (in_bom yes) (on_board yes)\n");
+        // 添加属性
+        for (id, property) in self.properties.iter().enumerate() {
+            let id = if version < SYMBOL_PROPERTY_ID_DROPPED_VERSION { format!(" (id {})", id) } else { String::new() };
+            content.push_str(&format!("    (property \"{}\" \"{}\"{} (at {:.4} {:.4} 0)\n      (effects (font (size 1.27 1.27)){}))\n",
+                escape_string(&property.key), escape_string(&property.value), id, property.x, property.y, if property.visible { "" } else { " hide" }));
+        }
+        // 添加各单元
+        for (unit, elements) in &self.units {
+            content.push_str(&format!("    (symbol \"{}_{}_1\"\n", name, unit));
+            for element in elements {
+                content.push_str(element);
+                content.push('\n');
+            }
+            content.push_str("    )\n");
+        }
+        content.push_str("  )\n");
+
+        info!("生成符号{}，共{}个属性，{}个单元", self.name, self.properties.len(), self.units.len()); // 添加日志
+
+        content
+    }
+
+    /// 生成完整的kicad_sym文件内容
+    ///
+    /// # 返回值
+    ///
+    /// * `String` - 只包含本符号的完整kicad_sym文件内容
+    pub fn generate_content(&self) -> String {
+        let mut content = String::new();
+        content.push_str(&format!("(kicad_symbol_lib (version {}) (generator elibz2kicad)\n", SYMBOL_LIB_VERSION));
+        content.push_str(&self.generate_symbol(SYMBOL_LIB_VERSION));
+        content.push_str(")\n");
+        content
+    }
+}
+
+/// 已有的kicad_sym库文件
+///
+/// 只记录顶层符号条目的位置，插入、替换符号时文件的其它内容保持原样
+#[derive(Debug, Clone)]
+pub struct KicadSymLib {
+    /// 文件原始内容
+    content: String,
+    /// 顶层符号: (转义后的名称, 起始位置, 结束位置)，结束位置在右括号之后
+    symbols: Vec<(String, usize, usize)>,
+    /// 根节点右括号的位置
+    root_end: usize,
+    /// 文件格式版本
+    version: u32,
+    /// 换行符，插入的内容与原文件保持一致
+    line_ending: &'static str,
+}
+
+impl Default for KicadSymLib {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KicadSymLib {
+    /// 创建一个空的库
+    pub fn new() -> Self {
+        let content = format!("(kicad_symbol_lib (version {}) (generator elibz2kicad)\n)\n", SYMBOL_LIB_VERSION);
+        let root_end = content.len() - 2;
+        KicadSymLib {
+            content,
+            symbols: Vec::new(),
+            root_end,
+            version: SYMBOL_LIB_VERSION,
+            line_ending: "\n",
+        }
+    }
+
+    /// 解析kicad_sym文件内容
+    ///
+    /// # 参数
+    ///
+    /// * `content` - 文件内容
+    ///
+    /// # 返回值
+    ///
+    /// * `Result<KicadSymLib, String>` - 解析结果，格式不正确时返回错误信息
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let bytes = content.as_bytes();
+        let mut depth = 0usize;
+        let mut symbols = Vec::new();
+        let mut root_end = None;
+        let mut version = None;
+        // 当前顶层符号的起始位置
+        let mut symbol_start: Option<(usize, String)> = None;
+        let mut i = 0;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'"' => {
+                    // 跳过字符串
+                    i += 1;
+                    while i < bytes.len() && bytes[i] != b'"' {
+                        if bytes[i] == b'\\' {
+                            i += 1;
+                        }
+                        i += 1;
+                    }
+                    if i >= bytes.len() {
+                        return Err("字符串没有结束".to_string());
+                    }
+                }
+                b'(' => {
+                    if root_end.is_some() {
+                        return Err("根节点之后还有内容".to_string());
+                    }
+                    depth += 1;
+                    let (head, head_end) = read_token(content, i + 1);
+                    if depth == 1 && head != "kicad_symbol_lib" {
+                        return Err("不是kicad_sym文件".to_string());
+                    }
+                    if depth == 2 && head == "symbol" {
+                        let (name, _) = read_token(content, head_end);
+                        symbol_start = Some((i, name.trim_matches('"').to_string()));
+                    }
+                    if depth == 2 && head == "version" {
+                        let (value, _) = read_token(content, head_end);
+                        version = Some(value.parse::<u32>().map_err(|_| format!("版本号不正确: {}", value))?);
+                    }
+                }
+                b')' => {
+                    if depth == 0 {
+                        return Err("括号不匹配".to_string());
+                    }
+                    if depth == 2 {
+                        if let Some((start, name)) = symbol_start.take() {
+                            symbols.push((name, start, i + 1));
+                        }
+                    }
+                    depth -= 1;
+                    if depth == 0 {
+                        root_end = Some(i);
+                    }
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+        let root_end = match root_end {
+            Some(root_end) if depth == 0 => root_end,
+            _ => return Err("括号不匹配".to_string()),
+        };
+        // 没有版本号的按KiCad 6处理
+        let version = version.unwrap_or(SYMBOL_LIB_VERSION);
+        let line_ending = if content.contains("\r\n") { "\r\n" } else { "\n" };
+        info!("解析kicad_sym文件，版本{}，共{}个符号", version, symbols.len());
+        Ok(KicadSymLib {
+            content: content.to_string(),
+            symbols,
+            root_end,
+            version,
+            line_ending,
+        })
+    }
+
+    /// 是否已有同名符号
+    pub fn contains(&self, symbol_name: &str) -> bool {
+        let name = escape_string(symbol_name);
+        self.symbols.iter().any(|(n, _, _)| *n == name)
+    }
+
+    /// 插入符号，同名符号存在时替换
+    ///
+    /// # 参数
+    ///
+    /// * `symbol` - 要插入的符号
+    ///
+    /// # 返回值
+    ///
+    /// * `Result<(), String>` - 插入后的内容无法重新解析时返回错误信息
+    pub fn upsert_symbol(&mut self, symbol: &KicadSymData) -> Result<(), String> {
+        // 按目标文件的版本和换行符生成
+        let entry = symbol.generate_symbol(self.version).replace('\n', self.line_ending);
+        let name = escape_string(&symbol.name);
+        let mut content = String::with_capacity(self.content.len() + entry.len());
+        if let Some((_, start, end)) = self.symbols.iter().find(|(n, _, _)| *n == name) {
+            // 替换原有条目，条目前的缩进和之后的换行保持不变
+            info!("替换符号: {}", symbol.name);
+            content.push_str(&self.content[..*start]);
+            content.push_str(entry.trim());
+            content.push_str(&self.content[*end..]);
+        } else {
+            // 在根节点的右括号之前插入
+            info!("插入符号: {}", symbol.name);
+            content.push_str(&self.content[..self.root_end]);
+            if !content.ends_with('\n') {
+                content.push_str(self.line_ending);
+            }
+            content.push_str(&entry);
+            content.push_str(&self.content[self.root_end..]);
+        }
+        // 重新解析以更新符号位置
+        *self = KicadSymLib::parse(&content)?;
+        Ok(())
+    }
+
+    /// 获取文件内容
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+}
+
+// 读取从start开始的一个记号(跳过前导空白)，返回记号和结束位置
+fn read_token(content: &str, start: usize) -> (&str, usize) {
+    let bytes = content.as_bytes();
+    let mut i = start;
+    while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+        i += 1;
+    }
+    let token_start = i;
+    if i < bytes.len() && bytes[i] == b'"' {
+        i += 1;
+        while i < bytes.len() && bytes[i] != b'"' {
+            if bytes[i] == b'\\' {
+                i += 1;
+            }
+            i += 1;
+        }
+        i = (i + 1).min(bytes.len());
+    } else {
+        while i < bytes.len() && !bytes[i].is_ascii_whitespace() && bytes[i] != b'(' && bytes[i] != b')' {
+            i += 1;
+        }
+    }
+    (&content[token_start..i], i)
+}
+
+// mil转mm的函数
+pub fn mil_to_mm(mil: f64) -> f64 {
+    mil * 0.0254
+}
+
+// 原理图单位(10mil)转mm的函数
+fn sch_to_mm(value: f64) -> f64 {
+    value * 0.254
+}
+
+/// 转义S表达式中的字符串，处理反斜杠、双引号和换行
+///
+/// # 参数
+/// * `value` - 原始字符串
+///
+/// # 返回值
+/// * `String` - 可以直接放在双引号中的字符串
+pub fn escape_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// 符号图形的填充类型
+fn sym_fill(fill: bool) -> &'static str {
+    if fill { "background" } else { "none" }
+}
+
+
+/// 输入圆弧的起点、终点、角度计算出圆弧的第三点
+/// 
+/// # 参数
+///
+/// * `x1` - 圆弧起点的X坐标
+/// * `y1` - 圆弧起点的Y坐标
+/// * `x2` - 圆弧终点的X坐标
+/// * `y2` - 圆弧终点的Y坐标
+/// * `angle` - 圆弧的角度（角度）
+/// 
+/// # 返回值
+///
+fn calculate_arc_midpoint(x1: f64, y1: f64, x2: f64, y2: f64, angle: f64) -> (f64, f64, f64, f64, f64, f64) {
+    //将angle转换为弧度
+    let angle = angle.to_radians();
+    // 接近零角度或同一点
+    if angle.abs() < 1e-10 || (x1 - x2).hypot(y1 - y2) < 1e-10 {
+        return (x1, y1, x1, y1, x2, y2);
+    }
+
+    let mut x1 = x1;
+    let mut y1 = y1;
+    let mut x2 = x2;
+    let mut y2 = y2;
+    let abs_angle = angle.abs();
+
+    // 如果角度为负，说明用户想要顺时针，我们转换成等效的逆时针：交换起点终点
+    if angle < 0.0 {
+        std::mem::swap(&mut x1, &mut x2);
+        std::mem::swap(&mut y1, &mut y2);
+    }
+
+    // 现在统一按逆时针处理
+    let dx = x2 - x1;
+    let dy = y2 - y1;
+    let chord_len = dx.hypot(dy);
+
+    if chord_len < 1e-10 {
+        return (x1, y1, x1, y1, x2, y2);
+    }
+
+    let half_angle = abs_angle * 0.5;
+
+    // sin(half_angle) 必须合理
+    let sin_half = half_angle.sin();
+    if sin_half.abs() < 1e-10 {
+        return (x1, y1, x1, y1, x2, y2);
+    }
+
+    // 半径 R = chord_len / (2 * sin(half_angle))
+    let radius = chord_len / (2.0 * sin_half);
+
+    // 圆心到弦中点的距离：d = R * cos(half_angle)
+    let dist_to_center = radius * half_angle.cos();
+
+    // 弦中点
+    let mx = (x1 + x2) * 0.5;
+    let my = (y1 + y2) * 0.5;
+
+    // 单位向量：从起点到终点
+    let ex = dx / chord_len;
+    let ey = dy / chord_len;
+
+    // 法向量：逆时针旋转90度 => (-ey, ex)
+    let nx = -ey;
+    let ny = ex;
+
+    // 圆心 = 弦中点 + 法向量 * dist_to_center
+    let cx = mx + nx * dist_to_center;
+    let cy = my + ny * dist_to_center;
+
+    // 向量从圆心指向起点
+    let start_vec_x = x1 - cx;
+    let start_vec_y = y1 - cy;
+    let start_angle = start_vec_y.atan2(start_vec_x);
+
+    // 中点对应的角度：起始角 + 半个角度（逆时针）
+    let mid_angle = start_angle + half_angle;
+
+    // 计算中点
+    let mid_x = cx + radius * mid_angle.cos();
+    let mid_y = cy + radius * mid_angle.sin();
+
+    (x1, y1, mid_x, mid_y, x2, y2)
+}
+
+/// 点的绝对坐标变为相对坐标
+/// 
+/// # 参数
+/// * `pts` - 点的绝对坐标，格式为[x1, y1, x2, y2, ...]
+/// * `center_x` - 中心的X坐标
+/// * `center_y` - 中心的Y坐标
+/// 
+/// # 返回值
+/// * `Vec<f64>` - 相对坐标的(x1, y1, x2, y2, ...)格式
+pub fn absolute_to_relative(pts: &[f64], center_x: f64, center_y: f64) -> Vec<f64> {
+    let mut relative_pts = Vec::new();
+    for i in 0..pts.len() / 2 {
+        let x = pts[i * 2] - center_x;
+        let y = -(pts[i * 2 + 1] - center_y); // 修正Y坐标符号
+        relative_pts.push(x);
+        relative_pts.push(y);
+    }
+    relative_pts
+}
+
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Point {
+    /// 创建一个新的点
+    pub fn new(x: f64, y: f64) -> Self {
+        Point { x, y }
+    }
+
+    /// 计算两点之间的距离
+    fn distance(&self, other: &Point) -> f64 {
+        ((self.x - other.x).powi(2) + (self.y - other.y).powi(2)).sqrt()
+    }
+
+    /// 向量加法
+    fn add(&self, other: &Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+
+    /// 向量减法
+    fn sub(&self, other: &Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+
+    /// 向量数乘
+    fn mul(&self, scalar: f64) -> Point {
+        Point::new(self.x * scalar, self.y * scalar)
+    }
+
+    /// 向量旋转90度 (逆时针)
+    fn perp(&self) -> Point {
+        Point::new(-self.y, self.x)
+    }
+
+    /// 向量单位化
+    fn normalize(&self) -> Point {
+        let len = (self.x * self.x + self.y * self.y).sqrt();
+        if len > 1e-10 { // 避免除以零
+            Point::new(self.x / len, self.y / len)
+        } else {
+            Point::new(0.0, 0.0)
+        }
+    }
+}
+
+/// 使用线段拟合圆弧
+///
+/// # 参数
+/// * `start`: 圆弧的起点
+/// * `end`: 圆弧的终点
+/// * `angle_radians`: 圆弧的圆心角（弧度）。正值表示逆时针方向，负值表示顺时针方向。
+/// * `num_segments`: 用于拟合圆弧的线段数量（即平滑度）。至少为1。
+///
+/// # 返回值
+/// 返回一个包含拟合点的 `Vec<Point>`。向量的长度为 `num_segments + 1`。
+/// 第一个点是 `start`，最后一个点是 `end`。
+///
+/// # 注意事项
+/// * 如果 `start` 和 `end` 非常接近，则视为点，返回包含该点的向量。
+/// * 如果 `angle_radians` 非常接近 0，则视为直线，返回起点和终点。
+/// * 如果 `num_segments` 小于 1，则按 1 处理。
+pub fn fit_arc_with_lines(
+    start: Point,
+    end: Point,
+    angle_radians: f64,
+    num_segments: usize,
+) -> Vec<Point> {
+    let mut points = Vec::with_capacity(num_segments + 1);
+
+    // 处理特殊情况
+    let chord_vec = end.sub(&start);
+    let chord_length = chord_vec.distance(&Point::new(0.0, 0.0));
+
+    if chord_length < 1e-10 || angle_radians.abs() < 1e-10 {
+        // 如果点重合或角度为0，返回直线
+        points.push(start);
+        points.push(end);
+        return points;
+    }
+
+    if num_segments < 1 {
+         points.push(start);
+         points.push(end);
+         return points;
+    }
+
+    // 计算半径
+    // R = (chord_length / 2) / sin(angle / 2)
+    let half_angle = angle_radians.abs() / 2.0;
+    let sin_half_angle = half_angle.sin();
+    if sin_half_angle < 1e-10 {
+        // 角度接近180度的倍数，处理为直线或半圆
+         points.push(start);
+         points.push(end);
+         return points;
+    }
+    let radius = (chord_length / 2.0) / sin_half_angle;
+
+    // 计算弦的中点
+    let mid_point = start.add(&end).mul(0.5);
+
+    // 计算弦的垂直平分线方向向量 (单位向量)
+    // 垂直向量可以通过旋转90度得到
+    let perp_chord_unit = chord_vec.perp().normalize();
+
+    // 计算圆心到弦中点的距离
+    // d = R * cos(angle / 2)
+    let center_dist = radius * half_angle.cos();
+
+    // 确定圆心位置
+    // 圆心在垂直平分线上，距离中点为 center_dist
+    // 方向由 angle 的正负决定（逆时针/顺时针）
+    let center_offset = if angle_radians > 0.0 {
+        perp_chord_unit.mul(center_dist)
+    } else {
+        perp_chord_unit.mul(-center_dist)
+    };
+    let center = mid_point.add(&center_offset);
+
+    // 计算起点和终点相对于圆心的角度
+    let start_angle = (start.y - center.y).atan2(start.x - center.x);
+    // let end_angle = (end.y - center.y).atan2(end.x - center.x);
+    // 我们不直接使用 end_angle，而是通过 start_angle 和 angle_radians 计算
+
+    // 生成拟合点
+    points.push(start); // 添加起点
+
+    let angle_step = angle_radians / (num_segments as f64);
+    for i in 1..num_segments {
+        let current_angle = start_angle + (i as f64) * angle_step;
+        let x = center.x + radius * current_angle.cos();
+        let y = center.y + radius * current_angle.sin();
+        points.push(Point::new(x, y));
+    }
+
+    points.push(end); // 添加终点
+
+    points
+}
+
+
+/// 按弦高误差计算拟合圆弧需要的线段数
+///
+/// # 参数
+/// * `start`: 圆弧的起点
+/// * `end`: 圆弧的终点
+/// * `angle_radians`: 圆弧的圆心角（弧度）
+/// * `chord_tolerance`: 线段与圆弧之间允许的最大距离，与坐标单位相同
+///
+/// # 返回值
+/// 线段数量，至少为1
+pub fn arc_segment_count(start: &Point, end: &Point, angle_radians: f64, chord_tolerance: f64) -> usize {
+    let half_angle = angle_radians.abs() / 2.0;
+    let chord_length = start.distance(end);
+    if chord_length < 1e-10 || half_angle.sin() < 1e-10 || chord_tolerance <= 0.0 {
+        return 1;
+    }
+    let radius = (chord_length / 2.0) / half_angle.sin();
+    if chord_tolerance >= radius {
+        return 1;
+    }
+    // 每段线段对应的圆心角不超过 2 * acos(1 - tolerance / R)
+    let max_step = 2.0 * (1.0 - chord_tolerance / radius).acos();
+    ((angle_radians.abs() / max_step).ceil() as usize).max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbol(name: &str) -> KicadSymData {
+        let mut symbol = KicadSymData::new(name, "U");
+        symbol.add_rectangle(0, -2.54, 2.54, 2.54, -2.54, 0.254, false);
+        symbol
+    }
+
+    #[test]
+    fn symbol_content_is_exact() {
+        let mut symbol = KicadSymData::new("A\"B", "U");
+        symbol.set_property("Footprint", "FP:X");
+        symbol.set_property("LCSC", "C1");
+        symbol.set_property_position("Reference", 0.0, 20.0, true);
+        symbol.add_pin(1, "input", "line", "IN\\", "1", -40.0, 10.0, 0.0, 10.0, true);
+        symbol.add_rectangle(1, -30.0, 20.0, 30.0, -20.0, 1.0, true);
+        symbol.add_arc(2, 0.0, 10.0, 10.0, 0.0, 0.0, -10.0, 1.0);
+        symbol.add_text(2, "a\"b\nc", 0.0, 0.0, 90.0, None);
+        let expected = concat!(
+            "(kicad_symbol_lib (version 20211014) (generator elibz2kicad)\n",
+            "  (symbol \"A\\\"B\" (pin_names (offset 1.0160)) (in_bom yes) (on_board yes)\n",
+            "    (property \"Reference\" \"U\" (id 0) (at 0.0000 5.0800 0)\n      (effects (font (size 1.27 1.27))))\n",
+            "    (property \"Value\" \"A\\\"B\" (id 1) (at 0.0000 -2.5400 0)\n      (effects (font (size 1.27 1.27))))\n",
+            "    (property \"Footprint\" \"FP:X\" (id 2) (at 0.0000 0.0000 0)\n      (effects (font (size 1.27 1.27)) hide))\n",
+            "    (property \"Datasheet\" \"\" (id 3) (at 0.0000 0.0000 0)\n      (effects (font (size 1.27 1.27)) hide))\n",
+            "    (property \"LCSC\" \"C1\" (id 4) (at 0.0000 0.0000 0)\n      (effects (font (size 1.27 1.27)) hide))\n",
+            "    (symbol \"A\\\"B_1_1\"\n",
+            "      (pin input line (at -10.1600 2.5400 0) (length 2.5400)\n",
+            "        (name \"IN\\\\\" (effects (font (size 1.27 1.27))))\n",
+            "        (number \"1\" (effects (font (size 1.27 1.27)))))\n",
+            "      (rectangle (start -7.6200 5.0800) (end 7.6200 -5.0800)\n",
+            "        (stroke (width 0.2540) (type default) (color 0 0 0 0))\n",
+            "        (fill (type background)))\n",
+            "    )\n",
+            "    (symbol \"A\\\"B_2_1\"\n",
+            "      (arc (start 0.0000 2.5400) (mid 2.5400 0.0000) (end 0.0000 -2.5400)\n",
+            "        (stroke (width 0.2540) (type default) (color 0 0 0 0))\n",
+            "        (fill (type none)))\n",
+            "      (text \"a\\\"b\\nc\" (at 0.0000 0.0000 900)\n",
+            "        (effects (font (size 1.2700 1.2700))))\n",
+            "    )\n",
+            "  )\n",
+            ")\n",
+        );
+        assert_eq!(symbol.generate_content(), expected);
+    }
+
+    #[test]
+    fn symbol_hides_pins_and_property_ids() {
+        let mut symbol = KicadSymData::new("R", "R");
+        symbol.set_pin_names(0.0, true);
+        symbol.set_pin_numbers_hide(true);
+        symbol.add_pin(0, "passive", "line", "~", "2", 0.0, -20.0, 90.0, 10.0, false);
+        let expected = concat!(
+            "  (symbol \"R\" (pin_numbers hide) (pin_names (offset 0.0000) hide) (in_bom yes) (on_board yes)\n",
+            "    (property \"Reference\" \"R\" (at 0.0000 2.5400 0)\n      (effects (font (size 1.27 1.27))))\n",
+            "    (property \"Value\" \"R\" (at 0.0000 -2.5400 0)\n      (effects (font (size 1.27 1.27))))\n",
+            "    (property \"Footprint\" \"\" (at 0.0000 0.0000 0)\n      (effects (font (size 1.27 1.27)) hide))\n",
+            "    (property \"Datasheet\" \"\" (at 0.0000 0.0000 0)\n      (effects (font (size 1.27 1.27)) hide))\n",
+            "    (symbol \"R_0_1\"\n",
+            "      (pin passive line (at 0.0000 -5.0800 90) (length 2.5400) hide\n",
+            "        (name \"~\" (effects (font (size 1.27 1.27))))\n",
+            "        (number \"2\" (effects (font (size 1.27 1.27)))))\n",
+            "    )\n",
+            "  )\n",
+        );
+        assert_eq!(symbol.generate_symbol(SYMBOL_PROPERTY_ID_DROPPED_VERSION), expected);
+    }
+
+    #[test]
+    fn symbol_lib_keeps_property_ids_for_kicad6() {
+        let mut lib = KicadSymLib::parse("(kicad_symbol_lib (version 20211014) (generator kicad_symbol_editor)\n)\n").unwrap();
+        lib.upsert_symbol(&symbol("A")).unwrap();
+        assert!(lib.content().contains("(property \"Reference\" \"U\" (id 0) (at"));
+    }
+
+    #[test]
+    fn symbol_lib_drops_property_ids_for_newer_versions() {
+        let mut lib = KicadSymLib::parse("(kicad_symbol_lib (version 20231120) (generator \"kicad_symbol_editor\")\n)\n").unwrap();
+        lib.upsert_symbol(&symbol("A")).unwrap();
+        assert!(lib.content().contains("(property \"Reference\" \"U\" (at"));
+        assert!(!lib.content().contains("(id "));
+        assert!(lib.content().starts_with("(kicad_symbol_lib (version 20231120)"));
+    }
+
+    #[test]
+    fn symbol_lib_upsert_keeps_other_content() {
+        let original = concat!(
+            "(kicad_symbol_lib (version 20211014) (generator kicad_symbol_editor)\n",
+            "# hand edited, keep as is\n",
+            "  (symbol \"Keep\"   (in_bom yes)\t(on_board yes)\n",
+            "    (property \"Reference\" \"R\" (id 0) (at 0 0 0))\n",
+            "  )\n",
+            "\n",
+            "\t(symbol \"A\" (in_bom yes) (on_board yes)\n",
+            "  )   # trailing comment\n",
+            "  (symbol \"Tail\" (property \"Value\" \"x (y) \\\"z\\\"\" (id 1) (at 0 0 0)))\n",
+            ")\n",
+        );
+        let mut lib = KicadSymLib::parse(original).unwrap();
+
+        // 新符号插入在根节点的右括号之前，其余内容不变
+        let root_end = original.rfind(')').unwrap();
+        lib.upsert_symbol(&symbol("B")).unwrap();
+        let inserted = format!("{}{}{}", &original[..root_end], symbol("B").generate_symbol(20211014), &original[root_end..]);
+        assert_eq!(lib.content(), inserted);
+
+        // 替换同名符号，只有该符号的条目改变，前面的缩进和后面的注释保持不变
+        let start = inserted.find("(symbol \"A\"").unwrap();
+        let end = inserted.find("  )   # trailing").unwrap() + "  )".len();
+        let mut replacement = symbol("A");
+        replacement.set_property("Datasheet", "https://example.com/a.pdf");
+        lib.upsert_symbol(&replacement).unwrap();
+        let replaced = format!("{}{}{}", &inserted[..start], replacement.generate_symbol(20211014).trim(), &inserted[end..]);
+        assert_eq!(lib.content(), replaced);
+        assert!(lib.content().starts_with(&original[..original.find("\t(symbol \"A\"").unwrap() + 1]));
+        assert!(lib.content().contains("\n  )   # trailing comment\n  (symbol \"Tail\" (property \"Value\" \"x (y) \\\"z\\\"\" (id 1) (at 0 0 0)))\n"));
+        assert!(lib.contains("Keep") && lib.contains("A") && lib.contains("B") && lib.contains("Tail"));
+    }
+
+    #[test]
+    fn symbol_lib_keeps_crlf_line_endings() {
+        let content = "(kicad_symbol_lib (version 20211014) (generator kicad_symbol_editor)\r\n  (symbol \"A\" (in_bom yes) (on_board yes)\r\n  )\r\n)\r\n";
+        let mut lib = KicadSymLib::parse(content).unwrap();
+        lib.upsert_symbol(&symbol("A")).unwrap();
+        lib.upsert_symbol(&symbol("B")).unwrap();
+        assert!(lib.contains("A") && lib.contains("B"));
+        assert_eq!(lib.content().matches('\n').count(), lib.content().matches("\r\n").count());
+    }
+
+    #[test]
+    fn new_symbol_lib_uses_lf() {
+        let mut lib = KicadSymLib::new();
+        lib.upsert_symbol(&symbol("A")).unwrap();
+        assert!(lib.contains("A"));
+        assert!(!lib.content().contains('\r'));
+    }
+}
@@ -0,0 +1,13 @@
+//! elibz2kicad-core - 把立创EDA的.elibz元件库转换为KiCad符号和封装
+//!
+//! 不依赖GUI，`convert_elibz`只做转换，`convert_elibz_file`读取.elibz文件并写入KiCad库
+
+pub mod kicad_tool;
+pub mod jlc_model;
+pub mod jlc_tool;
+pub mod output;
+pub mod report;
+
+pub use jlc_tool::{convert_elibz, ConvertOptions, ConvertedFootprint, ConvertedLibrary, ConvertedSymbol};
+pub use output::{convert_elibz_file, write_footprints, write_symbols};
+pub use report::{ConversionReport, ConvertError, ItemKind, ItemReport, ItemStatus, Unconverted};
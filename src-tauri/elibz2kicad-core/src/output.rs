@@ -0,0 +1,271 @@
+//! output - 把转换结果写入KiCad库文件
+//!
+//! 封装写为目录下的.kicad_mod文件，符号插入到.kicad_sym库中
+
+use std::collections::HashSet;
+use std::path::Path;
+use log::{error, info};
+use crate::jlc_tool::{convert_elibz, ConvertOptions, ConvertedFootprint, ConvertedSymbol};
+use crate::kicad_tool::KicadSymLib;
+use crate::report::{ConversionReport, ConvertError, ItemReport, ItemStatus};
+
+/// 转换.elibz文件并写入KiCad库
+///
+/// # 参数
+///
+/// * `elibz_file` - .elibz文件路径
+/// * `output_dir` - kicad_mod文件目录路径，为空时跳过封装
+/// * `kicad_sym_file` - kicad_sym文件路径，为空时跳过符号，文件已存在时将符号插入到该库中
+/// * `replace_symbol` - 库中已有同名符号时是否替换
+/// * `options` - 转换选项
+///
+/// # 返回值
+///
+/// * `Result<ConversionReport, ConvertError>` - 各符号、封装的转换结果，整个文件无法处理时返回错误
+pub fn convert_elibz_file(elibz_file: &str, output_dir: &str, kicad_sym_file: &str, replace_symbol: bool, options: &ConvertOptions) -> Result<ConversionReport, ConvertError> {
+    info!("开始处理文件: {}", elibz_file);
+    let path = Path::new(elibz_file);
+    // 检查文件是否存在
+    if !path.exists() {
+        return Err(ConvertError::FileNotFound(elibz_file.to_string()));
+    }
+
+    // 判断是否为.elibz文件，扩展名不区分大小写
+    let is_elibz = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("elibz"));
+    if !is_elibz {
+        return Err(ConvertError::NotElibz(elibz_file.to_string()));
+    }
+    // 文件名去掉目录和扩展名
+    let file_name = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or("elibz");
+
+    let bytes = std::fs::read(elibz_file).map_err(|e| ConvertError::OpenArchive(e.to_string()))?;
+    let library = convert_elibz(file_name, &bytes, options)?;
+
+    let mut report = ConversionReport {
+        source: elibz_file.to_string(),
+        items: Vec::new(),
+    };
+    report.items.extend(write_symbols(library.symbols, kicad_sym_file, replace_symbol));
+    report.items.extend(write_footprints(library.footprints, output_dir));
+    report.items.extend(library.failed);
+    Ok(report)
+}
+
+/// 把符号写入.kicad_sym库，库文件只读写一次
+///
+/// # 参数
+///
+/// * `symbols` - 转换后的符号
+/// * `kicad_sym_path` - kicad_sym文件路径，为空时跳过，文件已存在时将符号插入到该库中
+/// * `replace_symbol` - 库中已有同名符号时是否替换
+///
+/// # 返回值
+///
+/// * `Vec<ItemReport>` - 各符号的转换结果
+pub fn write_symbols(symbols: Vec<ConvertedSymbol>, kicad_sym_path: &str, replace_symbol: bool) -> Vec<ItemReport> {
+    if symbols.is_empty() {
+        return Vec::new();
+    }
+    if kicad_sym_path.is_empty() {
+        return symbols.into_iter().map(|s| s.report.skipped("未指定符号库文件")).collect();
+    }
+
+    // 读取已有的库，不存在或为空时新建
+    let mut kicad_sym_lib = if Path::new(kicad_sym_path).exists() {
+        let content = match std::fs::read_to_string(kicad_sym_path) {
+            Ok(content) => content,
+            Err(e) => {
+                error!("读取符号库失败: {:?}", e);
+                return symbols.into_iter().map(|s| s.report.failed(format!("读取符号库失败: {}", e))).collect();
+            }
+        };
+        if content.trim().is_empty() {
+            KicadSymLib::new()
+        } else {
+            match KicadSymLib::parse(&content) {
+                Ok(lib) => lib,
+                Err(e) => {
+                    error!("解析符号库失败: {}", e);
+                    return symbols.into_iter().map(|s| s.report.failed(format!("解析符号库失败: {}", e))).collect();
+                }
+            }
+        }
+    } else {
+        KicadSymLib::new()
+    };
+
+    // 逐个插入或替换
+    let mut reports = Vec::new();
+    for ConvertedSymbol { symbol, report } in symbols {
+        if kicad_sym_lib.contains(&report.title) && !replace_symbol {
+            let message = format!("符号库中已有{}", report.title);
+            reports.push(report.skipped(message));
+            continue;
+        }
+        match kicad_sym_lib.upsert_symbol(&symbol) {
+            Ok(_) => reports.push(report),
+            Err(e) => {
+                error!("插入符号失败: {}", e);
+                reports.push(report.failed(format!("插入符号失败: {}", e)));
+            }
+        }
+    }
+
+    // 写入文件
+    match std::fs::write(kicad_sym_path, kicad_sym_lib.content()) {
+        Ok(_) => reports
+            .into_iter()
+            .map(|mut report| {
+                if report.status == ItemStatus::Converted {
+                    report.output_path = Some(kicad_sym_path.to_string());
+                }
+                report
+            })
+            .collect(),
+        Err(e) => {
+            error!("写入文件失败: {:?}", e);
+            reports
+                .into_iter()
+                .map(|report| match report.status {
+                    ItemStatus::Converted => report.failed(format!("写入符号文件失败: {}", e)),
+                    _ => report,
+                })
+                .collect()
+        }
+    }
+}
+
+/// 把封装逐个写为.kicad_mod文件，文件名中不允许的字符替换为下划线，
+/// 文件名与之前的封装相同(不区分大小写)时不写入，避免覆盖
+///
+/// # 参数
+///
+/// * `footprints` - 转换后的封装
+/// * `kicad_mod_path` - kicad_mod文件目录路径，为空时跳过
+///
+/// # 返回值
+///
+/// * `Vec<ItemReport>` - 各封装的转换结果
+pub fn write_footprints(footprints: Vec<ConvertedFootprint>, kicad_mod_path: &str) -> Vec<ItemReport> {
+    // 已写入的文件名，Windows和macOS的文件名不区分大小写
+    let mut written: HashSet<String> = HashSet::new();
+    footprints
+        .into_iter()
+        .map(|ConvertedFootprint { content, report }| {
+            if kicad_mod_path.is_empty() {
+                return report.skipped("未指定封装输出目录");
+            }
+            let file_name = format!("{}.kicad_mod", sanitize_file_name(&report.title));
+            if !written.insert(file_name.to_lowercase()) {
+                error!("封装文件名重复: {}", file_name);
+                return report.failed(format!("与另一个封装的文件名{}相同", file_name));
+            }
+            let output_path = Path::new(kicad_mod_path).join(file_name);
+            match std::fs::write(&output_path, content) {
+                Ok(_) => {
+                    let mut report = report;
+                    report.output_path = Some(output_path.to_string_lossy().into_owned());
+                    report
+                }
+                Err(e) => {
+                    error!("写入文件失败: {:?}", e);
+                    report.failed(format!("写入文件失败: {}", e))
+                }
+            }
+        })
+        .collect()
+}
+
+// Windows保留的设备名，不能用作文件名
+const RESERVED_FILE_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL",
+    "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9",
+    "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// 把标题转换为各平台都能使用的文件名
+///
+/// # 参数
+///
+/// * `title` - 符号或封装的标题
+///
+/// # 返回值
+///
+/// * `String` - 文件名，`/ \ : * ? " < > |`和控制字符替换为下划线，去掉末尾的点和空格，Windows的保留名称后加下划线
+pub fn sanitize_file_name(title: &str) -> String {
+    let mut name: String = title
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    // Windows下文件名末尾的点和空格会被去掉
+    let trimmed_len = name.trim_end_matches(['.', ' ']).len();
+    name.truncate(trimmed_len);
+    if name.is_empty() {
+        return "_".to_string();
+    }
+    // 带扩展名的保留名称同样不能使用，如"CON.txt"，在第一个点之前加下划线
+    let base_len = name.find('.').unwrap_or(name.len());
+    if RESERVED_FILE_NAMES.iter().any(|reserved| name[..base_len].eq_ignore_ascii_case(reserved)) {
+        name.insert(base_len, '_');
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::{ItemKind, ItemReport};
+
+    fn footprint(title: &str) -> ConvertedFootprint {
+        ConvertedFootprint { content: format!("(footprint \"{}\")\n", title), report: ItemReport::new(ItemKind::Footprint, title) }
+    }
+
+    #[test]
+    fn reserved_names_get_an_underscore() {
+        assert_eq!(sanitize_file_name("CON"), "CON_");
+        assert_eq!(sanitize_file_name("com1"), "com1_");
+        assert_eq!(sanitize_file_name("Lpt9.v2"), "Lpt9_.v2");
+        // 只是以保留名称开头的不变
+        assert_eq!(sanitize_file_name("CONN_2"), "CONN_2");
+        assert_eq!(sanitize_file_name("COM10"), "COM10");
+    }
+
+    #[test]
+    fn trailing_dots_and_spaces_are_removed() {
+        assert_eq!(sanitize_file_name("SOT-23. . "), "SOT-23");
+        assert_eq!(sanitize_file_name(" R0603"), " R0603");
+        // 去掉末尾后成为保留名称的也要处理
+        assert_eq!(sanitize_file_name("NUL ."), "NUL_");
+    }
+
+    #[test]
+    fn invalid_characters_are_replaced() {
+        assert_eq!(sanitize_file_name("A/B\\C:D*E?F\"G<H>I|J"), "A_B_C_D_E_F_G_H_I_J");
+        assert_eq!(sanitize_file_name("A\tB\nC\u{7f}"), "A_B_C_");
+        assert_eq!(sanitize_file_name("电阻 0603"), "电阻 0603");
+    }
+
+    #[test]
+    fn empty_names_become_an_underscore() {
+        assert_eq!(sanitize_file_name(""), "_");
+        assert_eq!(sanitize_file_name(". ."), "_");
+    }
+
+    #[test]
+    fn footprints_with_the_same_file_name_are_not_overwritten() {
+        let dir = std::env::temp_dir().join(format!("elibz2kicad-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let reports = write_footprints(vec![footprint("A/B"), footprint("a_b")], dir.to_str().unwrap());
+        assert_eq!(reports[0].status, ItemStatus::Converted);
+        assert_eq!(reports[1].status, ItemStatus::Failed);
+        assert_eq!(std::fs::read_to_string(dir.join("A_B.kicad_mod")).unwrap(), "(footprint \"A/B\")\n");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,221 @@
+//! report - 转换结果和错误类型
+//!
+//! 前端和命令行通过这些结构体获取每个符号、封装的转换状态，而不必匹配提示字符串
+
+use std::fmt;
+use log::warn;
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+/// 整个.elibz文件无法转换时的错误
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConvertError {
+    /// 文件不存在
+    FileNotFound(String),
+    /// 文件不是.elibz格式
+    NotElibz(String),
+    /// 无法打开文件或压缩包
+    OpenArchive(String),
+    /// 无法读取压缩包里的文件
+    ReadArchive(String),
+    /// 压缩包中缺少.json清单或.efoo、.esym文件
+    MissingFiles,
+    /// JSON清单无法解析
+    InvalidManifest(String),
+}
+
+impl ConvertError {
+    /// 错误类型名称，供前端区分错误
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ConvertError::FileNotFound(_) => "file_not_found",
+            ConvertError::NotElibz(_) => "not_elibz",
+            ConvertError::OpenArchive(_) => "open_archive",
+            ConvertError::ReadArchive(_) => "read_archive",
+            ConvertError::MissingFiles => "missing_files",
+            ConvertError::InvalidManifest(_) => "invalid_manifest",
+        }
+    }
+}
+
+impl fmt::Display for ConvertError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConvertError::FileNotFound(path) => write!(f, "文件不存在: {}", path),
+            ConvertError::NotElibz(path) => write!(f, "文件不是.elibz格式: {}", path),
+            ConvertError::OpenArchive(e) => write!(f, "无法打开压缩包: {}", e),
+            ConvertError::ReadArchive(e) => write!(f, "无法读取压缩包文件: {}", e),
+            ConvertError::MissingFiles => write!(f, "压缩包中缺少必要的.json .efoo .esym文件"),
+            ConvertError::InvalidManifest(e) => write!(f, "无法解析JSON文件: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ConvertError {}
+
+// 序列化为 {"kind": "...", "message": "..."}
+impl Serialize for ConvertError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("ConvertError", 2)?;
+        state.serialize_field("kind", self.kind())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
+
+/// 转换的对象类型
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemKind {
+    Symbol,
+    Footprint,
+}
+
+/// 单个符号或封装的转换状态
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemStatus {
+    /// 已转换并写入
+    Converted,
+    /// 跳过，如未指定输出路径、库中已有同名符号
+    Skipped,
+    /// 转换或写入失败
+    Failed,
+}
+
+/// 未能转换的图元
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Unconverted {
+    /// 在.efoo/.esym文件中的行号，从1开始
+    pub line: usize,
+    /// 图元类型，如PAD、POLY
+    pub kind: String,
+    /// 原因
+    pub reason: String,
+}
+
+/// 单个符号或封装的转换结果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ItemReport {
+    /// 符号或封装
+    pub kind: ItemKind,
+    /// 标题
+    pub title: String,
+    /// 状态
+    pub status: ItemStatus,
+    /// 输出文件路径
+    pub output_path: Option<String>,
+    /// 失败或跳过的原因
+    pub message: Option<String>,
+    /// 警告
+    pub warnings: Vec<String>,
+    /// 未能转换的图元
+    pub unconverted: Vec<Unconverted>,
+}
+
+impl ItemReport {
+    /// 创建一个转换中的结果，状态默认为已转换
+    pub fn new(kind: ItemKind, title: &str) -> Self {
+        ItemReport {
+            kind,
+            title: title.to_string(),
+            status: ItemStatus::Converted,
+            output_path: None,
+            message: None,
+            warnings: Vec::new(),
+            unconverted: Vec::new(),
+        }
+    }
+
+    /// 记录未能转换的图元，同时写入日志
+    ///
+    /// # 参数
+    ///
+    /// * `line` - 行号
+    /// * `kind` - 图元类型
+    /// * `reason` - 原因
+    pub fn unconverted(&mut self, line: usize, kind: &str, reason: impl Into<String>) {
+        let reason = reason.into();
+        warn!("第{}行{}: {},未完全解析", line, kind, reason);
+        self.unconverted.push(Unconverted { line, kind: kind.to_string(), reason });
+    }
+
+    /// 记录警告，同时写入日志
+    pub fn warn(&mut self, message: impl Into<String>) {
+        let message = message.into();
+        warn!("{}", message);
+        self.warnings.push(message);
+    }
+
+    /// 标记为跳过
+    pub fn skipped(mut self, message: impl Into<String>) -> Self {
+        self.status = ItemStatus::Skipped;
+        self.message = Some(message.into());
+        self
+    }
+
+    /// 标记为失败
+    pub fn failed(mut self, message: impl Into<String>) -> Self {
+        self.status = ItemStatus::Failed;
+        self.message = Some(message.into());
+        self
+    }
+}
+
+/// 一个.elibz文件的转换结果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConversionReport {
+    /// .elibz文件路径
+    pub source: String,
+    /// 各符号、封装的转换结果
+    pub items: Vec<ItemReport>,
+}
+
+impl ConversionReport {
+    /// 所有符号、封装中未能转换的图元总数
+    pub fn unconverted_count(&self) -> usize {
+        self.items.iter().map(|item| item.unconverted.len()).sum()
+    }
+
+    /// 是否有符号或封装转换失败
+    pub fn has_failures(&self) -> bool {
+        self.items.iter().any(|item| item.status == ItemStatus::Failed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn convert_error_is_kind_and_message() {
+        assert_eq!(
+            serde_json::to_value(ConvertError::FileNotFound("a.elibz".to_string())).unwrap(),
+            json!({"kind": "file_not_found", "message": "文件不存在: a.elibz"})
+        );
+        assert_eq!(
+            serde_json::to_value(ConvertError::MissingFiles).unwrap(),
+            json!({"kind": "missing_files", "message": "压缩包中缺少必要的.json .efoo .esym文件"})
+        );
+    }
+
+    #[test]
+    fn item_report_fields() {
+        let mut report = ItemReport::new(ItemKind::Footprint, "R0603");
+        report.unconverted(3, "PAD", "未知的焊盘形状: X");
+        report.warn("警告");
+        assert_eq!(
+            serde_json::to_value(report.failed("无法写入")).unwrap(),
+            json!({
+                "kind": "footprint",
+                "title": "R0603",
+                "status": "failed",
+                "output_path": null,
+                "message": "无法写入",
+                "warnings": ["警告"],
+                "unconverted": [{"line": 3, "kind": "PAD", "reason": "未知的焊盘形状: X"}],
+            })
+        );
+    }
+}
@@ -0,0 +1,75 @@
+//! 命令行的退出码
+
+use std::io::{Cursor, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+// 每个测试使用单独的临时目录
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("elibz2kicad-cli-{}-{}", name, uuid::Uuid::new_v4()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+// 在目录中生成.elibz文件
+fn write_elibz(dir: &Path, files: &[(&str, &[u8])]) -> String {
+    let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, content) in files {
+        writer.start_file(*name, zip::write::SimpleFileOptions::default()).unwrap();
+        writer.write_all(content).unwrap();
+    }
+    let path = dir.join("LIB.elibz");
+    std::fs::write(&path, writer.finish().unwrap().into_inner()).unwrap();
+    path.to_str().unwrap().to_string()
+}
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_elibz2kicad-cli")).args(args).output().unwrap()
+}
+
+const MANIFEST: &[u8] = br#"{"footprints":{"a":{"title":"A"},"b":{"title":"B"}}}"#;
+const PAD: &[u8] = br#"["PAD","e1",0,"",1,"1",0,0,0,null,["RECT",10,10,0],[],0,0,0,1,0,0,0,0,0]"#;
+
+#[test]
+fn converted_files_exit_with_zero() {
+    let dir = temp_dir("converted");
+    let elibz = write_elibz(&dir, &[("device.json", MANIFEST), ("a.efoo", PAD), ("b.efoo", PAD)]);
+    let footprints = dir.join("out");
+    let output = run(&["convert", &elibz, "--footprints", footprints.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(footprints.join("A.kicad_mod").exists());
+    assert!(footprints.join("B.kicad_mod").exists());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn failed_item_exits_with_one() {
+    let dir = temp_dir("failed");
+    // a.efoo不是UTF-8，该封装失败，其它封装照常输出
+    let elibz = write_elibz(&dir, &[("device.json", MANIFEST), ("a.efoo", b"\xff\xfe"), ("b.efoo", PAD)]);
+    let footprints = dir.join("out");
+    let output = run(&["convert", &elibz, "--footprints", footprints.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("封装 A: 失败"));
+    assert!(footprints.join("B.kicad_mod").exists());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn missing_file_exits_with_one() {
+    let dir = temp_dir("missing");
+    let missing = dir.join("missing.elibz");
+    let output = run(&["convert", missing.to_str().unwrap(), "--footprints", dir.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("文件不存在"));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn bad_arguments_exit_with_two() {
+    let output = run(&["convert", "a.elibz", "--footprints", "out", "--chord-tolerance", "abc"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with("--chord-tolerance 不是正数: abc"));
+    assert_eq!(run(&["convert", "a.elibz"]).status.code(), Some(2));
+}
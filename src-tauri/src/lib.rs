@@ -1,5 +1,26 @@
-mod kicad_tool;
-mod jlc_tool;
+use elibz2kicad_core::{convert_elibz_file, ConversionReport, ConvertError, ConvertOptions};
+
+/// 对.elib文件进行处理函数，转换逻辑在elibz2kicad-core中
+///
+/// # 参数
+///
+/// * `elibz_file` - .elibz文件路径
+/// * `output_dir` - kicad_mod文件目录路径，为空时跳过封装
+/// * `kicad_sym_file` - kicad_sym文件路径，为空时跳过符号，文件已存在时将符号插入到该库中
+/// * `replace_symbol` - 库中已有同名符号时是否替换
+/// * `polygon_arcs` - 填充图形中的圆弧直接输出，需要KiCad 7及以上
+///
+/// # 返回值
+///
+/// * `Result<ConversionReport, ConvertError>` - 各符号、封装的转换结果，整个文件无法处理时返回错误
+#[tauri::command]
+fn process_elib_file(elibz_file: String, output_dir: String, kicad_sym_file: String, replace_symbol: bool, polygon_arcs: bool) -> Result<ConversionReport, ConvertError> {
+  let options = ConvertOptions {
+    polygon_arcs,
+    ..ConvertOptions::default()
+  };
+  convert_elibz_file(&elibz_file, &output_dir, &kicad_sym_file, replace_symbol, &options)
+}
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -15,7 +36,7 @@ pub fn run() {
       Ok(())
     })
     .plugin(tauri_plugin_dialog::init())
-    .invoke_handler(tauri::generate_handler![jlc_tool::process_elib_file])
+    .invoke_handler(tauri::generate_handler![process_elib_file])
     .run(tauri::generate_context!())
     .expect("error while running tauri application");
 }
@@ -1,8 +1,8 @@
 import { useState, useEffect } from 'react'
 import './App.css'
-import { open } from '@tauri-apps/plugin-dialog'
+import { open, save } from '@tauri-apps/plugin-dialog'
 import { invoke } from '@tauri-apps/api/core';
-import { Button, Space, Typography, Divider, Layout, Modal } from 'antd';
+import { Button, Space, Typography, Divider, Layout, Modal, Checkbox } from 'antd';
 import { FolderOpenOutlined, FileOutlined, PlayCircleOutlined } from '@ant-design/icons';
 import { BrowserRouter as Router, Routes, Route } from 'react-router-dom';
 import Sidebar from './components/Sidebar';
@@ -11,10 +11,66 @@ import MarkdownViewer from './components/MarkdownViewer';
 
 const { Header, Content, Footer, Sider } = Layout;
 
+/** 未能转换的图元 */
+interface Unconverted {
+  line: number;
+  kind: string;
+  reason: string;
+}
+
+/** 单个符号或封装的转换结果 */
+interface ItemReport {
+  kind: 'symbol' | 'footprint';
+  title: string;
+  status: 'converted' | 'skipped' | 'failed';
+  output_path: string | null;
+  message: string | null;
+  warnings: string[];
+  unconverted: Unconverted[];
+}
+
+/** 一个.elibz文件的转换结果 */
+interface ConversionReport {
+  source: string;
+  items: ItemReport[];
+}
+
+/** 整个.elibz文件无法转换时的错误 */
+interface ConvertError {
+  kind: string;
+  message: string;
+}
+
+const KIND_TEXT = { symbol: '符号', footprint: '封装' };
+const STATUS_TEXT = { converted: '已转换', skipped: '已跳过', failed: '失败' };
+
+/**
+ * @brief 格式化转换结果
+ * @details 每个符号、封装一段，列出状态、输出路径、警告和未能转换的图元
+ */
+const formatReport = (report: ConversionReport) => {
+  const lines = [`${report.source}:`];
+  for (const item of report.items) {
+    lines.push(`  ${KIND_TEXT[item.kind]} ${item.title}: ${STATUS_TEXT[item.status]}${item.message ? `，${item.message}` : ''}`);
+    if (item.output_path) {
+      lines.push(`    输出: ${item.output_path}`);
+    }
+    for (const warning of item.warnings) {
+      lines.push(`    警告: ${warning}`);
+    }
+    for (const u of item.unconverted) {
+      lines.push(`    未完全解析: 第${u.line}行 ${u.kind} ${u.reason}`);
+    }
+  }
+  return lines.join('\n');
+};
+
 function App() {
   const [elibzFiles, setElibzFiles] = useState<string[]>([])
   const [outputDir, setOutputDir] = useState('')
-  //const [kicadSymFile, setKicadSymFile] = useState('')
+  const [kicadSymFile, setKicadSymFile] = useState('')
+  const [replaceSymbol, setReplaceSymbol] = useState(false)
+  const [polygonArcs, setPolygonArcs] = useState(false)
   const [collapsed, setCollapsed] = useState(false);
   const [isConverted, setIsConverted] = useState(false);
   const [conversionResult, setConversionResult] = useState('');
@@ -24,24 +80,22 @@ function App() {
     setIsConverted(false);
     setConversionResult('');
 
-    try {
-      // 为每个文件分别调用后端API
-      const results = [];
-      for (const file of elibzFiles) {
-        const result = await invoke('process_elib_file', { elibzFile: file, outputDir: outputDir, kicadSymFile: "" });
-        results.push(`${file}: ${result}`);
+    // 为每个文件分别调用后端API，单个文件失败不影响其它文件
+    const results = [];
+    for (const file of elibzFiles) {
+      try {
+        const report = await invoke<ConversionReport>('process_elib_file', { elibzFile: file, outputDir: outputDir, kicadSymFile: kicadSymFile, replaceSymbol: replaceSymbol, polygonArcs: polygonArcs });
+        results.push(formatReport(report));
+      } catch (error) {
+        const message = (error as ConvertError).message ?? `${error}`;
+        results.push(`${file}: 转换失败: ${message}`);
       }
-      
-      const finalResult = results.join('\n\n');
-      setConversionResult(finalResult);
-      setIsConverted(true);
-      setIsModalVisible(true);
-    } catch (error) {
-      Modal.info({
-        title: '转换结果',
-        content: `转换失败: ${error}`,
-      });
     }
+
+    const finalResult = results.join('\n\n');
+    setConversionResult(finalResult);
+    setIsConverted(true);
+    setIsModalVisible(true);
   }
 
   useEffect(() => {
@@ -88,23 +142,20 @@ function App() {
 
   /**
    * @brief 选择.kicad_sym文件
-   * @details 打开文件选择对话框，选择.kicad_sym文件并获取其绝对路径
-   *
-   * NOTE: 此功能暂时隐藏
+   * @details 打开保存对话框，选择已有的或输入新的.kicad_sym文件并获取其绝对路径，符号会插入到该库中，文件不存在时新建
    */
-  // const pickKicadSymFile = async () => {
-  //   const selected = await open({
-  //     multiple: false,
-  //     filters: [{
-  //       name: 'KiCad Symbol Files',
-  //       extensions: ['kicad_sym']
-  //     }]
-  //   });
-  //   if (selected) {
-  //     // 确保获取的是绝对路径
-  //     setKicadSymFile(selected as string);
-  //   }
-  // };
+  const pickKicadSymFile = async () => {
+    const selected = await save({
+      filters: [{
+        name: 'KiCad Symbol Files',
+        extensions: ['kicad_sym']
+      }]
+    });
+    if (selected) {
+      // 确保获取的是绝对路径，并带有.kicad_sym扩展名
+      setKicadSymFile(selected.endsWith('.kicad_sym') ? selected : `${selected}.kicad_sym`);
+    }
+  };
 
   return (
     <Router>
@@ -145,9 +196,12 @@ function App() {
                           {outputDir}
                         </Typography.Text>
                       </div>
+                      <Checkbox style={{ marginTop: '8px' }} checked={polygonArcs} onChange={(e) => setPolygonArcs(e.target.checked)}>
+                        填充图形中的圆弧不拟合为线段（需要KiCad 7及以上）
+                      </Checkbox>
                     </div>
                     <Divider />
-                    {/* <div>
+                    <div>
                       <Typography.Text strong>选择输出符号到.kicad_sym文件:</Typography.Text>
                       <div style={{ display: 'flex', alignItems: 'center', marginTop: '8px' }}>
                         <Button icon={<FileOutlined />} onClick={pickKicadSymFile}>
@@ -157,7 +211,10 @@ function App() {
                           {kicadSymFile}
                         </Typography.Text>
                       </div>
-                    </div> */}
+                      <Checkbox style={{ marginTop: '8px' }} checked={replaceSymbol} onChange={(e) => setReplaceSymbol(e.target.checked)}>
+                        替换库中的同名符号
+                      </Checkbox>
+                    </div>
                     <Divider />
                     <div style={{ textAlign: 'center' }}>
                 